@@ -0,0 +1,205 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::api::pinboard::{PinboardClients, PinboardEndpoint};
+use crate::domain::accounts::AuthStyle;
+use crate::infra::db::TempDb;
+use crate::security::credentials::CredentialStore;
+use crate::security::memory_store::MemoryStore;
+use crate::security::token_store::CredentialControl;
+use crate::AppState;
+
+/// The token `app_state` saves for the default account.
+pub const TOKEN: &str = "me:SECRET";
+
+/// A scripted answer for the next request the mock server receives.
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    /// 429 with a `Retry-After` header.
+    RateLimited { retry_after_secs: u64 },
+    /// This status with an empty body, e.g. 503.
+    Status(u16),
+    /// This status with Pinboard's XML `<result code="..." />` body.
+    Xml { status: u16, code: String },
+    /// 200 with this JSON body, e.g. an existing bookmark for `posts/get`.
+    Json(Value),
+    /// Waits this long, then answers as if nothing was scripted.
+    Delay(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Endpoint below the API root, e.g. `posts/add`.
+    pub path: String,
+    pub params: HashMap<String, String>,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    replies: VecDeque<MockReply>,
+    requests: Vec<MockRequest>,
+}
+
+/// An in-process stand-in for the Pinboard API on a local port. Requests
+/// are answered from the scripted replies in order, then with a successful
+/// default for the endpoint.
+pub struct MockPinboard {
+    base_url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockPinboard {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let server_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, server_state.clone()));
+            }
+        });
+
+        Self { base_url, state }
+    }
+
+    /// An endpoint for this server with no write pacing and short timeouts.
+    pub fn endpoint(&self) -> PinboardEndpoint {
+        PinboardEndpoint {
+            base_url: self.base_url.clone(),
+            auth_style: AuthStyle::QueryToken,
+            write_interval: Duration::ZERO,
+            read_timeout: Duration::from_millis(300),
+            write_timeout: Duration::from_millis(300),
+            sync_timeout: Duration::from_millis(300),
+        }
+    }
+
+    pub fn reply(&self, reply: MockReply) {
+        self.state.lock().unwrap().replies.push_back(reply);
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<MockRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path == path)
+            .collect()
+    }
+}
+
+/// App state on a fresh temp database, sending its requests to `server` and
+/// holding `TOKEN` for the default account in memory; no keyring, credential
+/// file or environment variable is touched. Keep the returned guard alive
+/// for as long as the state is used.
+pub fn app_state(server: &MockPinboard, name: &str) -> (Arc<AppState>, TempDb) {
+    let db = TempDb::new(name);
+
+    let tokens = MemoryStore::default();
+    tokens.set_token("default", TOKEN).unwrap();
+    let state = AppState {
+        pinboard: PinboardClients::with_defaults(server.endpoint()),
+        ..AppState::new(
+            db.path().to_str().unwrap(),
+            Arc::new(CredentialControl::new(
+                db.path().with_extension("credentials.json"),
+            )),
+            Box::new(tokens),
+        )
+        .unwrap()
+    };
+    (Arc::new(state), db)
+}
+
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let request = MockRequest {
+        path: path.trim_start_matches("/v1/").to_string(),
+        params: url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        headers: lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect(),
+    };
+
+    let scripted = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        state.replies.pop_front()
+    };
+    let reply = match scripted {
+        Some(MockReply::Delay(delay)) => {
+            tokio::time::sleep(delay).await;
+            None
+        }
+        other => other,
+    };
+
+    let (status, headers, body) = match reply {
+        Some(MockReply::RateLimited { retry_after_secs }) => (
+            429,
+            format!("Retry-After: {retry_after_secs}\r\n"),
+            String::new(),
+        ),
+        Some(MockReply::Status(status)) => (status, String::new(), String::new()),
+        Some(MockReply::Xml { status, code }) => (
+            status,
+            "Content-Type: text/xml\r\n".to_string(),
+            format!("<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n<result code=\"{code}\" />\n"),
+        ),
+        Some(MockReply::Json(body)) => (200, String::new(), body.to_string()),
+        Some(MockReply::Delay(_)) | None => {
+            (200, String::new(), default_body(&request).to_string())
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status} Mock\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn default_body(request: &MockRequest) -> Value {
+    match request.path.as_str() {
+        "posts/get" | "posts/recent" => json!({ "posts": [] }),
+        "posts/all" => json!([]),
+        "posts/suggest" => json!([{ "popular": [] }, { "recommended": [] }]),
+        "tags/get" => json!({}),
+        "user/api_token" => {
+            let token = request.params.get("auth_token").map(String::as_str);
+            let secret = token
+                .and_then(|token| token.split_once(':'))
+                .map(|(_, secret)| secret);
+            json!({ "result": secret.unwrap_or_default() })
+        }
+        _ => json!({ "result_code": "done" }),
+    }
+}
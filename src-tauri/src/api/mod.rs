@@ -1 +1,3 @@
+#[cfg(test)]
+pub mod mock_server;
 pub mod pinboard;
@@ -1,15 +1,37 @@
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use reqwest::{header, StatusCode, Url};
 use serde_json::{json, Value};
 use tokio::sync::Mutex;
 
-use crate::domain::bookmark::{BookmarkPayload, ExistingBookmark, TagSuggestions};
+use crate::domain::accounts::{AccountBackend, AuthStyle};
+use crate::domain::bookmark::{
+    merge_payload, pinboard_datetime, BookmarkPayload, ExistingBookmark, SubmitIntent,
+    TagSuggestions, UserTag,
+};
+use crate::domain::conflict::changed_since;
+use crate::domain::tags::tag_strings;
+use crate::domain::validation::{validate_payload, FieldError};
 
 const PINBOARD_BASE: &str = "https://api.pinboard.in/v1";
 const PINBOARD_MIN_INTERVAL_SECS: u64 = 3;
 const PINBOARD_READ_TIMEOUT_SECS: u64 = 4;
+const PINBOARD_WRITE_TIMEOUT_SECS: u64 = 30;
+const PINBOARD_SYNC_TIMEOUT_SECS: u64 = 5 * 60;
+const PINBOARD_RECENT_MAX: usize = 100;
 const DEFAULT_RETRY_AFTER_SECS: i64 = 30;
+/// Result codes that mean the token was rejected, for servers that answer
+/// with a result code instead of HTTP 401/403.
+const AUTH_RESULT_CODES: &[&str] = &[
+    "401 forbidden",
+    "403 forbidden",
+    "forbidden",
+    "unauthorized",
+    "invalid token",
+    "invalid auth token",
+];
 
 #[derive(Debug, thiserror::Error)]
 pub enum PinboardError {
@@ -26,6 +48,19 @@ pub enum PinboardError {
     Http { status: u16, message: String },
     #[error("pinboard API error: {code}")]
     Api { code: String, retryable: bool },
+    /// Pinboard rejected the API token (HTTP 401/403).
+    #[error("unauthorized: {message}")]
+    Unauthorized { message: String },
+    /// An update's base version no longer matches Pinboard. `remote` is the
+    /// current copy, or `None` if the bookmark was deleted.
+    #[error("bookmark changed on Pinboard")]
+    Conflict {
+        remote: Option<Box<ExistingBookmark>>,
+    },
+    /// Merging with the saved bookmark would break Pinboard's field limits,
+    /// e.g. notes grown too long; nothing was written.
+    #[error("merged bookmark is invalid")]
+    InvalidMerge { field_errors: Vec<FieldError> },
 }
 
 impl PinboardError {
@@ -38,6 +73,9 @@ impl PinboardError {
                 *status == 408 || *status == 425 || *status == 429 || *status >= 500
             }
             Self::Api { retryable, .. } => *retryable,
+            Self::Unauthorized { .. } => false,
+            Self::Conflict { .. } => false,
+            Self::InvalidMerge { .. } => false,
         }
     }
 
@@ -58,29 +96,141 @@ impl PinboardError {
             | Self::RateLimited { message, .. }
             | Self::Http { message, .. } => message.clone(),
             Self::Api { code, .. } => code.clone(),
+            Self::Unauthorized { .. } => {
+                "Pinboard rejected the API token; check it in the token settings".to_string()
+            }
+            Self::Conflict { remote: Some(_) } => {
+                "This bookmark was changed on Pinboard after you opened it".to_string()
+            }
+            Self::Conflict { remote: None } => {
+                "This bookmark was deleted on Pinboard after you opened it".to_string()
+            }
+            Self::InvalidMerge { field_errors } => format!(
+                "Merging with the saved bookmark would break Pinboard's limits: {}",
+                field_errors
+                    .iter()
+                    .map(|error| error.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            ),
+        }
+    }
+}
+
+/// Where the client sends requests, how it authenticates and how it paces
+/// them. The default is the public Pinboard API with its
+/// one-write-per-three-seconds limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinboardEndpoint {
+    /// API root without a trailing slash, e.g. `https://api.pinboard.in/v1`.
+    pub base_url: String,
+    pub auth_style: AuthStyle,
+    /// Minimum time between two writes.
+    pub write_interval: Duration,
+    pub read_timeout: Duration,
+    pub write_timeout: Duration,
+    /// For `posts/all`, which can take minutes on a large collection but must
+    /// not hold the sync lock forever.
+    pub sync_timeout: Duration,
+}
+
+impl Default for PinboardEndpoint {
+    fn default() -> Self {
+        Self {
+            base_url: PINBOARD_BASE.to_string(),
+            auth_style: AuthStyle::QueryToken,
+            write_interval: Duration::from_secs(PINBOARD_MIN_INTERVAL_SECS),
+            read_timeout: Duration::from_secs(PINBOARD_READ_TIMEOUT_SECS),
+            write_timeout: Duration::from_secs(PINBOARD_WRITE_TIMEOUT_SECS),
+            sync_timeout: Duration::from_secs(PINBOARD_SYNC_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl PinboardEndpoint {
+    /// This endpoint with the account's backend in place of the Pinboard
+    /// defaults. Timeouts are kept.
+    pub fn with_backend(&self, backend: Option<&AccountBackend>) -> Self {
+        match backend {
+            Some(backend) => Self {
+                base_url: backend.base_url.clone(),
+                auth_style: backend.auth_style,
+                write_interval: Duration::from_secs(backend.write_interval_secs),
+                ..self.clone()
+            },
+            None => self.clone(),
+        }
+    }
+}
+
+/// One client per account, so each backend gets its own base URL, auth
+/// style and write pacing.
+pub struct PinboardClients {
+    defaults: PinboardEndpoint,
+    clients: std::sync::Mutex<HashMap<String, Arc<PinboardClient>>>,
+}
+
+impl PinboardClients {
+    pub fn new() -> Self {
+        Self::with_defaults(PinboardEndpoint::default())
+    }
+
+    /// Accounts without a backend of their own use `defaults`.
+    pub fn with_defaults(defaults: PinboardEndpoint) -> Self {
+        Self {
+            defaults,
+            clients: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The account's client, replaced when its backend settings changed.
+    pub fn for_account(
+        &self,
+        account: &str,
+        backend: Option<&AccountBackend>,
+    ) -> Arc<PinboardClient> {
+        let endpoint = self.defaults.with_backend(backend);
+        let mut clients = self
+            .clients
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match clients.get(account) {
+            Some(client) if client.endpoint == endpoint => client.clone(),
+            _ => {
+                let client = Arc::new(PinboardClient::with_endpoint(endpoint));
+                clients.insert(account.to_string(), client.clone());
+                client
+            }
         }
     }
 }
 
 pub struct PinboardClient {
     client: reqwest::Client,
+    endpoint: PinboardEndpoint,
     last_write_call: Mutex<Option<Instant>>,
 }
 
 impl PinboardClient {
     pub fn new() -> Self {
+        Self::with_endpoint(PinboardEndpoint::default())
+    }
+
+    pub fn with_endpoint(endpoint: PinboardEndpoint) -> Self {
         Self {
             client: reqwest::Client::new(),
+            endpoint,
             last_write_call: Mutex::new(None),
         }
     }
 
     async fn wait_write_rate_limit(&self) {
+        let interval = self.endpoint.write_interval;
         let mut guard = self.last_write_call.lock().await;
         if let Some(last) = *guard {
             let elapsed = last.elapsed();
-            if elapsed < Duration::from_secs(PINBOARD_MIN_INTERVAL_SECS) {
-                tokio::time::sleep(Duration::from_secs(PINBOARD_MIN_INTERVAL_SECS) - elapsed).await;
+            if elapsed < interval {
+                tokio::time::sleep(interval - elapsed).await;
             }
         }
         *guard = Some(Instant::now());
@@ -89,26 +239,36 @@ impl PinboardClient {
     async fn get_json(
         &self,
         path: &str,
+        token: &str,
         params: &[(&str, String)],
-        timeout: Option<Duration>,
+        timeout: Duration,
     ) -> Result<Value, PinboardError> {
-        let mut url = Url::parse(&format!("{PINBOARD_BASE}/{path}")).map_err(|_| {
-            PinboardError::InvalidResponse {
+        let base = self.endpoint.base_url.trim_end_matches('/');
+        let mut url =
+            Url::parse(&format!("{base}/{path}")).map_err(|_| PinboardError::InvalidResponse {
                 message: "invalid Pinboard URL".to_string(),
-            }
-        })?;
+            })?;
         {
             let mut query = url.query_pairs_mut();
             query.append_pair("format", "json");
+            if self.endpoint.auth_style == AuthStyle::QueryToken {
+                query.append_pair("auth_token", token);
+            }
             for (k, v) in params {
                 query.append_pair(k, v);
             }
         }
 
         let mut request = self.client.get(url);
-        if let Some(timeout) = timeout {
-            request = request.timeout(timeout);
+        match self.endpoint.auth_style {
+            AuthStyle::QueryToken => {}
+            AuthStyle::Bearer => request = request.bearer_auth(token),
+            AuthStyle::Basic => {
+                let (user, password) = token.split_once(':').unwrap_or((token, ""));
+                request = request.basic_auth(user, Some(password));
+            }
         }
+        request = request.timeout(timeout);
 
         let response = request
             .send()
@@ -130,6 +290,12 @@ impl PinboardClient {
             });
         }
 
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(PinboardError::Unauthorized {
+                message: extract_error_message(&body),
+            });
+        }
+
         if !status.is_success() {
             return Err(PinboardError::Http {
                 status: status.as_u16(),
@@ -155,59 +321,124 @@ impl PinboardClient {
         }
     }
 
+    /// Saves the bookmark and returns what was written, which for a merge is
+    /// the merged bookmark rather than `payload`.
     pub async fn add_bookmark(
         &self,
         token: &str,
         payload: &BookmarkPayload,
+    ) -> Result<BookmarkPayload, PinboardError> {
+        if matches!(payload.intent, SubmitIntent::Merge) {
+            return self.merge_bookmark(token, payload).await;
+        }
+        if let (SubmitIntent::Update, Some(base)) = (&payload.intent, payload.base.as_deref()) {
+            self.ensure_unchanged(token, base).await?;
+        }
+        self.post_bookmark(token, payload).await?;
+        Ok(payload.clone())
+    }
+
+    /// Re-reads the bookmark right before an update and refuses to overwrite
+    /// it if it no longer matches the version the edit started from.
+    async fn ensure_unchanged(
+        &self,
+        token: &str,
+        base: &ExistingBookmark,
     ) -> Result<(), PinboardError> {
-        self.wait_write_rate_limit().await;
+        match self.get_existing_bookmark(token, &base.url).await? {
+            Some(remote) if !changed_since(base, &remote) => Ok(()),
+            remote => Err(PinboardError::Conflict {
+                remote: remote.map(Box::new),
+            }),
+        }
+    }
 
-        let replace = match payload.intent {
-            crate::domain::bookmark::SubmitIntent::Create => "no",
-            crate::domain::bookmark::SubmitIntent::Update => "yes",
+    /// Reads the bookmark currently on Pinboard and writes its merge with
+    /// `payload`, keeping the original date. Creates the bookmark if the URL
+    /// is not saved yet.
+    async fn merge_bookmark(
+        &self,
+        token: &str,
+        payload: &BookmarkPayload,
+    ) -> Result<BookmarkPayload, PinboardError> {
+        let written = match self.get_existing_bookmark(token, &payload.url).await? {
+            Some(existing) => merge_payload(&existing, payload),
+            None => BookmarkPayload {
+                intent: SubmitIntent::Create,
+                ..payload.clone()
+            },
         };
+        // The capture was checked on its own; the merge can still outgrow the
+        // limits, e.g. appended notes.
+        let field_errors = validate_payload(&written);
+        if !field_errors.is_empty() {
+            return Err(PinboardError::InvalidMerge { field_errors });
+        }
+        self.post_bookmark(token, &written).await?;
+        Ok(written)
+    }
+
+    async fn post_bookmark(
+        &self,
+        token: &str,
+        payload: &BookmarkPayload,
+    ) -> Result<(), PinboardError> {
+        self.wait_write_rate_limit().await;
 
-        let tags = payload.tags.join(" ");
         let result = self
             .get_json(
                 "posts/add",
-                &[
-                    ("auth_token", token.to_string()),
-                    ("url", payload.url.clone()),
-                    ("description", payload.title.clone()),
-                    ("extended", payload.notes.clone()),
-                    ("tags", tags),
-                    ("replace", replace.to_string()),
-                    (
-                        "shared",
-                        if payload.private {
-                            "no".to_string()
-                        } else {
-                            "yes".to_string()
-                        },
-                    ),
-                    (
-                        "toread",
-                        if payload.read_later {
-                            "yes".to_string()
-                        } else {
-                            "no".to_string()
-                        },
-                    ),
-                ],
-                None,
+                token,
+                &add_bookmark_params(payload),
+                self.endpoint.write_timeout,
             )
             .await?;
+        expect_done(&result, "posts/add")
+    }
 
-        let code = extract_result_code(&result).ok_or_else(|| PinboardError::InvalidResponse {
-            message: "missing result code from posts/add".to_string(),
-        })?;
+    pub async fn delete_bookmark(&self, token: &str, url: &str) -> Result<(), PinboardError> {
+        self.wait_write_rate_limit().await;
 
-        if code.eq_ignore_ascii_case("done") {
-            Ok(())
-        } else {
-            Err(classify_api_code(&code))
-        }
+        let result = self
+            .get_json(
+                "posts/delete",
+                token,
+                &[("url", url.to_string())],
+                self.endpoint.write_timeout,
+            )
+            .await?;
+
+        expect_done(&result, "posts/delete")
+    }
+
+    pub async fn rename_tag(&self, token: &str, old: &str, new: &str) -> Result<(), PinboardError> {
+        self.wait_write_rate_limit().await;
+
+        let result = self
+            .get_json(
+                "tags/rename",
+                token,
+                &[("old", old.to_string()), ("new", new.to_string())],
+                self.endpoint.write_timeout,
+            )
+            .await?;
+
+        expect_done(&result, "tags/rename")
+    }
+
+    pub async fn delete_tag(&self, token: &str, tag: &str) -> Result<(), PinboardError> {
+        self.wait_write_rate_limit().await;
+
+        let result = self
+            .get_json(
+                "tags/delete",
+                token,
+                &[("tag", tag.to_string())],
+                self.endpoint.write_timeout,
+            )
+            .await?;
+
+        expect_done(&result, "tags/delete")
     }
 
     pub async fn suggest_tags(
@@ -218,8 +449,9 @@ impl PinboardClient {
         let value = self
             .get_json(
                 "posts/suggest",
-                &[("auth_token", token.to_string()), ("url", url.to_string())],
-                Some(Duration::from_secs(PINBOARD_READ_TIMEOUT_SECS)),
+                token,
+                &[("url", url.to_string())],
+                self.endpoint.read_timeout,
             )
             .await?;
 
@@ -231,13 +463,28 @@ impl PinboardClient {
         })
     }
 
-    pub async fn get_user_tags(&self, token: &str) -> Result<Vec<String>, PinboardError> {
+    /// Checks a `username:TOKEN` token against `user/api_token` and returns
+    /// the username it belongs to.
+    pub async fn verify_token(&self, token: &str) -> Result<String, PinboardError> {
         let value = self
-            .get_json(
-                "tags/get",
-                &[("auth_token", token.to_string())],
-                Some(Duration::from_secs(PINBOARD_READ_TIMEOUT_SECS)),
-            )
+            .get_json("user/api_token", token, &[], self.endpoint.read_timeout)
+            .await?;
+
+        let (username, secret) = token.split_once(':').unwrap_or(("", token));
+        match value.get("result").and_then(Value::as_str) {
+            Some(result) if result.eq_ignore_ascii_case(secret) => Ok(username.to_string()),
+            Some(_) => Err(PinboardError::Unauthorized {
+                message: "token does not match the account".to_string(),
+            }),
+            None => Err(PinboardError::InvalidResponse {
+                message: "missing result from user/api_token".to_string(),
+            }),
+        }
+    }
+
+    pub async fn get_user_tags(&self, token: &str) -> Result<Vec<UserTag>, PinboardError> {
+        let value = self
+            .get_json("tags/get", token, &[], self.endpoint.read_timeout)
             .await?;
 
         Ok(extract_user_tags(&value))
@@ -251,8 +498,9 @@ impl PinboardClient {
         let value = self
             .get_json(
                 "posts/get",
-                &[("auth_token", token.to_string()), ("url", url.to_string())],
-                Some(Duration::from_secs(PINBOARD_READ_TIMEOUT_SECS)),
+                token,
+                &[("url", url.to_string()), ("meta", "yes".to_string())],
+                self.endpoint.read_timeout,
             )
             .await?;
 
@@ -262,45 +510,117 @@ impl PinboardClient {
             .cloned()
             .unwrap_or_default();
 
-        let Some(first) = posts.first() else {
-            return Ok(None);
-        };
+        Ok(posts.first().map(|post| parse_post(post, url)))
+    }
+
+    /// Most recent bookmarks, newest first. Pinboard caps `count` at 100.
+    pub async fn get_recent_bookmarks(
+        &self,
+        token: &str,
+        count: usize,
+    ) -> Result<Vec<ExistingBookmark>, PinboardError> {
+        let value = self
+            .get_json(
+                "posts/recent",
+                token,
+                &[("count", count.clamp(1, PINBOARD_RECENT_MAX).to_string())],
+                self.endpoint.read_timeout,
+            )
+            .await?;
+
+        let posts = value
+            .get("posts")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        Ok(posts
+            .iter()
+            .map(|post| parse_post(post, ""))
+            .filter(|bookmark| !bookmark.url.is_empty())
+            .collect())
+    }
+
+    /// Downloads the whole collection (`posts/all`). Pinboard allows this
+    /// once every five minutes, so callers are expected to cache the result.
+    pub async fn get_all_bookmarks(
+        &self,
+        token: &str,
+    ) -> Result<Vec<ExistingBookmark>, PinboardError> {
+        let value = self
+            .get_json("posts/all", token, &[], self.endpoint.sync_timeout)
+            .await?;
+
+        let posts = value.as_array().cloned().unwrap_or_default();
+        Ok(posts
+            .iter()
+            .map(|post| parse_post(post, ""))
+            .filter(|bookmark| !bookmark.url.is_empty())
+            .collect())
+    }
+}
+
+fn add_bookmark_params(payload: &BookmarkPayload) -> Vec<(&'static str, String)> {
+    let replace = match payload.intent {
+        SubmitIntent::Create => "no",
+        SubmitIntent::Update | SubmitIntent::Merge => "yes",
+    };
+    let yes_no = |flag: bool| if flag { "yes" } else { "no" }.to_string();
+
+    let mut params = vec![
+        ("url", payload.url.clone()),
+        ("description", payload.title.clone()),
+        ("extended", payload.notes.clone()),
+        ("tags", tag_strings(&payload.tags).join(" ")),
+        ("replace", replace.to_string()),
+        ("shared", yes_no(!payload.private)),
+        ("toread", yes_no(payload.read_later)),
+    ];
+    if let Some(dt) = payload.created_at.as_deref().and_then(pinboard_datetime) {
+        params.push(("dt", dt));
+    }
+    params
+}
+
+fn parse_post(post: &Value, fallback_url: &str) -> ExistingBookmark {
+    let tags = post
+        .get("tags")
+        .or_else(|| post.get("tag"))
+        .and_then(Value::as_str)
+        .unwrap_or("")
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
 
-        let tags = first
-            .get("tags")
-            .or_else(|| first.get("tag"))
+    ExistingBookmark {
+        url: post
+            .get("href")
+            .or_else(|| post.get("url"))
+            .and_then(Value::as_str)
+            .unwrap_or(fallback_url)
+            .to_string(),
+        title: post
+            .get("description")
             .and_then(Value::as_str)
             .unwrap_or("")
-            .split_whitespace()
-            .map(ToString::to_string)
-            .collect::<Vec<_>>();
-
-        Ok(Some(ExistingBookmark {
-            url: first
-                .get("href")
-                .or_else(|| first.get("url"))
-                .and_then(Value::as_str)
-                .unwrap_or(url)
-                .to_string(),
-            title: first
-                .get("description")
-                .and_then(Value::as_str)
-                .unwrap_or("")
-                .to_string(),
-            notes: first
-                .get("extended")
-                .and_then(Value::as_str)
-                .unwrap_or("")
-                .to_string(),
-            tags,
-            private: matches!(first.get("shared").and_then(Value::as_str), Some("no")),
-            read_later: matches!(first.get("toread").and_then(Value::as_str), Some("yes")),
-            time: first
-                .get("time")
-                .and_then(Value::as_str)
-                .unwrap_or("")
-                .to_string(),
-        }))
+            .to_string(),
+        notes: post
+            .get("extended")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+        tags,
+        private: matches!(post.get("shared").and_then(Value::as_str), Some("no")),
+        read_later: matches!(post.get("toread").and_then(Value::as_str), Some("yes")),
+        time: post
+            .get("time")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+        meta: post
+            .get("meta")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
     }
 }
 
@@ -321,6 +641,18 @@ fn extract_result_code(value: &Value) -> Option<String> {
         .map(ToString::to_string)
 }
 
+fn expect_done(value: &Value, endpoint: &str) -> Result<(), PinboardError> {
+    let code = extract_result_code(value).ok_or_else(|| PinboardError::InvalidResponse {
+        message: format!("missing result code from {endpoint}"),
+    })?;
+
+    if code.eq_ignore_ascii_case("done") {
+        Ok(())
+    } else {
+        Err(classify_api_code(&code))
+    }
+}
+
 fn extract_result_code_from_text(text: &str) -> Option<String> {
     let marker = "code=\"";
     let start = text.find(marker)? + marker.len();
@@ -338,17 +670,15 @@ fn classify_api_code(code: &str) -> PinboardError {
         };
     }
 
-    let non_retryable = [
-        "item already exists",
-        "invalid",
-        "missing",
-        "not found",
-        "forbidden",
-        "unauthorized",
-        "auth",
-    ]
-    .iter()
-    .any(|needle| lower.contains(needle));
+    if AUTH_RESULT_CODES.contains(&lower.trim()) {
+        return PinboardError::Unauthorized {
+            message: code.to_string(),
+        };
+    }
+
+    let non_retryable = ["item already exists", "invalid", "missing", "not found"]
+        .iter()
+        .any(|needle| lower.contains(needle));
 
     let retryable = !non_retryable
         || ["something went wrong", "temporar", "timeout", "unavailable"]
@@ -436,7 +766,7 @@ fn extract_tag_list(value: &Value, key: &str) -> Vec<String> {
     Vec::new()
 }
 
-fn extract_user_tags(value: &Value) -> Vec<String> {
+fn extract_user_tags(value: &Value) -> Vec<UserTag> {
     let Some(tags_obj) = value.as_object() else {
         return Vec::new();
     };
@@ -457,5 +787,215 @@ fn extract_user_tags(value: &Value) -> Vec<String> {
         count_b.cmp(count_a).then_with(|| tag_a.cmp(tag_b))
     });
 
-    tags_with_count.into_iter().map(|(tag, _)| tag).collect()
+    tags_with_count
+        .into_iter()
+        .map(|(tag, count)| UserTag { tag, count })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        add_bookmark_params, classify_api_code, PinboardClient, PinboardClients, PinboardError,
+    };
+    use crate::api::mock_server::{MockPinboard, MockReply, TOKEN};
+    use crate::domain::accounts::{AccountBackend, AuthStyle};
+    use crate::domain::bookmark::{BookmarkPayload, ExistingBookmark, SubmitIntent};
+    use crate::domain::tags::Tag;
+
+    fn param<'a>(params: &'a [(&'static str, String)], key: &str) -> Option<&'a str> {
+        params
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn payload(intent: SubmitIntent, created_at: Option<&str>) -> BookmarkPayload {
+        BookmarkPayload {
+            url: "https://example.com/".to_string(),
+            title: "Example".to_string(),
+            notes: String::new(),
+            tags: vec![Tag::parse("a").unwrap(), Tag::parse("b").unwrap()],
+            private: true,
+            read_later: false,
+            intent,
+            created_at: created_at.map(ToString::to_string),
+            base: None,
+        }
+    }
+
+    #[test]
+    fn create_without_time_sends_no_dt() {
+        let params = add_bookmark_params(&payload(SubmitIntent::Create, None));
+        assert_eq!(param(&params, "replace"), Some("no"));
+        assert_eq!(param(&params, "tags"), Some("a b"));
+        assert_eq!(param(&params, "shared"), Some("no"));
+        assert_eq!(param(&params, "toread"), Some("no"));
+        assert_eq!(param(&params, "dt"), None);
+    }
+
+    #[test]
+    fn update_from_existing_bookmark_keeps_its_time() {
+        let existing = ExistingBookmark {
+            url: "https://example.com/".to_string(),
+            title: "Example".to_string(),
+            notes: String::new(),
+            tags: Vec::new(),
+            private: false,
+            read_later: true,
+            time: "2019-03-04T05:06:07Z".to_string(),
+            meta: String::new(),
+        };
+        let params = add_bookmark_params(&existing.update_payload());
+        assert_eq!(param(&params, "replace"), Some("yes"));
+        assert_eq!(param(&params, "dt"), Some("2019-03-04T05:06:07Z"));
+    }
+
+    #[test]
+    fn explicit_creation_time_is_converted_to_utc() {
+        let params = add_bookmark_params(&payload(
+            SubmitIntent::Create,
+            Some("2021-06-01T12:30:00+02:00"),
+        ));
+        assert_eq!(param(&params, "dt"), Some("2021-06-01T10:30:00Z"));
+
+        let invalid = add_bookmark_params(&payload(SubmitIntent::Update, Some("")));
+        assert_eq!(param(&invalid, "dt"), None);
+    }
+
+    #[test]
+    fn auth_codes_are_unauthorized_not_retryable() {
+        for code in ["401 Forbidden", "invalid token", "Unauthorized"] {
+            let err = classify_api_code(code);
+            assert!(matches!(err, PinboardError::Unauthorized { .. }), "{code}");
+            assert!(!err.is_retryable());
+        }
+        assert!(matches!(
+            classify_api_code("item already exists"),
+            PinboardError::Api {
+                retryable: false,
+                ..
+            }
+        ));
+        assert!(classify_api_code("something went wrong").is_retryable());
+        for code in ["missing author", "tag token too long"] {
+            let err = classify_api_code(code);
+            assert!(matches!(err, PinboardError::Api { .. }), "{code}");
+        }
+    }
+
+    #[tokio::test]
+    async fn server_failures_map_to_errors() {
+        let server = MockPinboard::start().await;
+        let client = PinboardClient::with_endpoint(server.endpoint());
+        let bookmark = payload(SubmitIntent::Create, None);
+
+        server.reply(MockReply::RateLimited {
+            retry_after_secs: 7,
+        });
+        let err = client.add_bookmark(TOKEN, &bookmark).await.unwrap_err();
+        assert_eq!(err.retry_after_secs(), Some(7));
+
+        server.reply(MockReply::Status(503));
+        let err = client.add_bookmark(TOKEN, &bookmark).await.unwrap_err();
+        assert!(matches!(err, PinboardError::Http { status: 503, .. }));
+        assert!(err.is_retryable());
+
+        server.reply(MockReply::Xml {
+            status: 500,
+            code: "something went wrong".to_string(),
+        });
+        let err = client.add_bookmark(TOKEN, &bookmark).await.unwrap_err();
+        assert_eq!(err.message_for_user(), "something went wrong");
+        assert!(err.is_retryable());
+
+        server.reply(MockReply::Xml {
+            status: 200,
+            code: "item already exists".to_string(),
+        });
+        let err = client.add_bookmark(TOKEN, &bookmark).await.unwrap_err();
+        assert!(!err.is_retryable());
+
+        server.reply(MockReply::Delay(Duration::from_secs(2)));
+        let err = client.add_bookmark(TOKEN, &bookmark).await.unwrap_err();
+        assert!(matches!(err, PinboardError::Network { .. }));
+        assert!(err.is_retryable());
+
+        client.add_bookmark(TOKEN, &bookmark).await.unwrap();
+        let adds = server.requests_to("posts/add");
+        assert_eq!(adds.len(), 6);
+        assert_eq!(adds[5].params["url"], "https://example.com/");
+        assert_eq!(adds[5].params["auth_token"], TOKEN);
+    }
+
+    #[tokio::test]
+    async fn full_sync_gives_up_after_its_timeout() {
+        let server = MockPinboard::start().await;
+        let client = PinboardClient::with_endpoint(server.endpoint());
+
+        server.reply(MockReply::Delay(Duration::from_secs(2)));
+        let err = client.get_all_bookmarks(TOKEN).await.unwrap_err();
+        assert!(matches!(err, PinboardError::Network { .. }));
+    }
+
+    #[tokio::test]
+    async fn verify_token_reports_the_username() {
+        let server = MockPinboard::start().await;
+        let client = PinboardClient::with_endpoint(server.endpoint());
+
+        assert_eq!(client.verify_token(TOKEN).await.unwrap(), "me");
+
+        server.reply(MockReply::Status(401));
+        assert!(matches!(
+            client.verify_token(TOKEN).await,
+            Err(PinboardError::Unauthorized { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn accounts_use_their_own_backend_and_auth_style() {
+        let pinboard = MockPinboard::start().await;
+        let self_hosted = MockPinboard::start().await;
+        let clients = PinboardClients::with_defaults(pinboard.endpoint());
+        let bearer = AccountBackend {
+            base_url: self_hosted.endpoint().base_url,
+            auth_style: AuthStyle::Bearer,
+            write_interval_secs: 0,
+        };
+
+        clients
+            .for_account("default", None)
+            .delete_bookmark(TOKEN, "https://a.example/")
+            .await
+            .unwrap();
+        clients
+            .for_account("work", Some(&bearer))
+            .delete_bookmark("secret", "https://b.example/")
+            .await
+            .unwrap();
+
+        let requests = pinboard.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].params["auth_token"], TOKEN);
+        let work = &self_hosted.requests()[0];
+        assert_eq!(work.params["url"], "https://b.example/");
+        assert!(!work.params.contains_key("auth_token"));
+        assert_eq!(work.headers["authorization"], "Bearer secret");
+
+        let basic = AccountBackend {
+            auth_style: AuthStyle::Basic,
+            ..bearer
+        };
+        clients
+            .for_account("work", Some(&basic))
+            .delete_bookmark("me:pw", "https://b.example/")
+            .await
+            .unwrap();
+        assert_eq!(
+            self_hosted.requests()[1].headers["authorization"],
+            "Basic bWU6cHc="
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     env, fs,
     path::PathBuf,
     sync::{Arc, OnceLock},
@@ -7,16 +7,59 @@ use std::{
 };
 
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Runtime, State};
 
+use crate::api::pinboard::PinboardError;
+use crate::backup::archive::{
+    validate_archive, BackupArchive, BackupError, BackupSummary, RestoreMode,
+};
 use crate::dedupe::service::check_duplicate_for_url;
+use crate::domain::accounts::{
+    validate_account_name, validate_backend, Account, AccountBackend, AuthStyle, DEFAULT_ACCOUNT,
+};
 use crate::domain::bookmark::{
-    normalize_url, parse_tags, BookmarkPayload, DuplicateCheckResult, TagSuggestions,
+    normalize_url, url_host, BookmarkPayload, DuplicateCheckResult, ExistingBookmark, SubmitIntent,
+    TagSuggestions, UserTag,
+};
+use crate::domain::bundles::{expand_bundles, validate_bundles, BundleExpansion, TagBundle};
+use crate::domain::conflict::{build_conflict, changed_since, BookmarkConflict};
+use crate::domain::rules::{
+    apply_rule_outcome, evaluate_rules, validate_rules, DomainRule, RuleOutcome,
+};
+use crate::domain::tags::{
+    normalize_tags, parse_tag_list, preview_tags, strip_private_tags, tag_strings,
+    TagNormalization, TagPreview,
 };
-use crate::queue::worker::process_due_items;
-use crate::security::token_store::TokenStoreError;
+use crate::domain::validation::{validate_payload, validate_tag, FieldError};
+use crate::export::formats::{render_export, ExportFormat};
+use crate::import::formats::{detect_format, parse_import, ImportFormat};
+use crate::import::service::spawn_pending_imports;
+use crate::import::store::ImportJob;
+use crate::infra::db::now_unix;
+use crate::library::query::SearchQuery;
+use crate::library::service::{search_library, sync_library};
+use crate::queue::store::QueueOperation;
+use crate::queue::worker::{execute_operation, pause_for_auth, process_due_items};
+use crate::security::accounts::{
+    account_settings, active_credentials, credentials_for, Credentials,
+};
+use crate::security::credentials::{CredentialError, CredentialSettings, CredentialStatus};
+use crate::security::sealed;
+use crate::settings::store::{
+    ACCOUNTS_KEY, CREDENTIALS_KEY, DOMAIN_RULES_KEY, TAG_BUNDLES_KEY, TAG_NORMALIZATION_KEY,
+};
+use crate::tagging::ranking::{rank_tags, RankedTag, RankingContext};
+use crate::tagging::service::cached_user_tags;
+use crate::undo::store::UNDO_WINDOW_SECS;
 use crate::AppState;
 
+const TAG_HISTORY_LIMIT: usize = 500;
+const RANKED_TAG_LIMIT: usize = 30;
+const SEARCH_RESULT_LIMIT: usize = 50;
+const RECENT_BOOKMARK_LIMIT: usize = 20;
+const INBOX_LIMIT: usize = 500;
+const IMPORT_JOB_LIMIT: usize = 20;
+
 static TITLE_FETCH_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 
 fn title_fetch_client() -> &'static reqwest::Client {
@@ -29,27 +72,92 @@ fn title_fetch_client() -> &'static reqwest::Client {
     })
 }
 
-fn map_token_store_error(error: TokenStoreError) -> String {
-    match error {
-        TokenStoreError::StorageUnavailable(_) =>
-            "System keyring is unavailable or locked. Ensure a Secret Service provider (GNOME Keyring/KWallet) is running and unlocked.".to_string(),
-        other => other.to_string(),
-    }
-}
-
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionInfo {
     pub token_configured: bool,
+    /// Tokens are kept in an encrypted file that has not been unlocked yet.
+    pub credentials_locked: bool,
+    pub active_account: String,
     pub queue_stats: crate::queue::store::QueueStats,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub name: String,
+    pub username: String,
+    pub token_configured: bool,
+    /// `None` when the account uses Pinboard itself.
+    pub backend: Option<AccountBackend>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountList {
+    pub active: String,
+    pub accounts: Vec<AccountInfo>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitResult {
     pub status: String,
     pub message: String,
     pub queued: bool,
+    pub field_errors: Vec<FieldError>,
+    /// Set when `status` is "conflict".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict: Option<BookmarkConflict>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedConflict {
+    pub id: i64,
+    pub conflict: BookmarkConflict,
+    /// Why the current remote version could not be read; `conflict.remote`
+    /// is then unknown rather than deleted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagMutationResult {
+    pub status: String,
+    pub message: String,
+    pub queued: bool,
+    pub affected: Vec<UserTag>,
+    pub affected_bookmarks: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteBookmarkResult {
+    /// `confirm` when nothing was deleted yet, otherwise `sent` or `queued`.
+    pub status: String,
+    pub message: String,
+    pub queued: bool,
+    pub bookmark: Option<ExistingBookmark>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoInfo {
+    pub url: String,
+    /// `delete` if the submission created the bookmark, `restore` otherwise.
+    pub action: String,
+    pub expires_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkList {
+    pub bookmarks: Vec<ExistingBookmark>,
+    /// True when Pinboard was unreachable and the list came from the local copy.
+    pub offline: bool,
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -68,36 +176,247 @@ pub struct OmarchyTheme {
 
 #[tauri::command]
 pub async fn init_session(state: State<'_, Arc<AppState>>) -> Result<SessionInfo, String> {
-    let token_configured = state
-        .token_store
-        .get_token()
-        .map_err(map_token_store_error)?
-        .is_some();
+    let active_account = account_settings(&state)?.active;
+    let (token_configured, credentials_locked) = match state.token_store.get_token(&active_account)
+    {
+        Ok(token) => (token.is_some(), false),
+        Err(CredentialError::Locked) => (false, true),
+        Err(err) => return Err(err.message_for_user()),
+    };
 
     let queue_stats = state.queue_store.stats().map_err(|e| e.to_string())?;
 
     Ok(SessionInfo {
         token_configured,
+        credentials_locked,
+        active_account,
         queue_stats,
     })
 }
 
+/// Verifies and stores a token for `account` (the active account when
+/// omitted), adding the account if it is new. Returns the Pinboard username.
 #[tauri::command]
-pub async fn save_token(state: State<'_, Arc<AppState>>, token: String) -> Result<(), String> {
+pub async fn save_token(
+    state: State<'_, Arc<AppState>>,
+    token: String,
+    account: Option<String>,
+) -> Result<String, String> {
     let clean = token.trim();
-    if !clean.contains(':') {
+    let mut settings = account_settings(&state)?;
+    let account = match account {
+        Some(name) => validate_account_name(&name).map_err(|e| e.to_string())?,
+        None => settings.active.clone(),
+    };
+    let backend = settings.backend(&account).cloned();
+    let auth_style = backend
+        .as_ref()
+        .map(|backend| backend.auth_style)
+        .unwrap_or_default();
+    if auth_style != AuthStyle::Bearer && !clean.contains(':') {
         return Err("Pinboard token should look like username:TOKEN".to_string());
     }
 
+    // Checked before storing so a mistyped token never reaches the queue.
+    let client = state.pinboard.for_account(&account, backend.as_ref());
+    let username = match client.verify_token(clean).await {
+        Ok(username) => username,
+        // Not every Pinboard-compatible server implements `user/api_token`.
+        Err(PinboardError::Http { status: 404, .. }) if backend.is_some() => clean
+            .split_once(':')
+            .map(|(username, _)| username.to_string())
+            .unwrap_or_default(),
+        Err(PinboardError::Unauthorized { .. }) => {
+            return Err(
+                "Pinboard rejected this token; copy it again from the Pinboard settings page"
+                    .to_string(),
+            )
+        }
+        Err(other) => {
+            return Err(format!(
+                "Could not verify the token with Pinboard: {}",
+                other.message_for_user()
+            ))
+        }
+    };
+
+    state
+        .token_store
+        .set_token(&account, clean)
+        .map_err(|e| e.message_for_user())?;
+    settings.upsert(&account, &username);
+    state
+        .settings_store
+        .set(ACCOUNTS_KEY, &settings)
+        .map_err(|e| e.to_string())?;
+    state.queue_store.resume(&account);
+    if account == settings.active {
+        clear_account_caches(&state)?;
+    }
+    Ok(username)
+}
+
+/// Removes the token of `account` (the active account when omitted). Named
+/// accounts are forgotten entirely; their queued items wait until a token is
+/// saved under the same name again.
+#[tauri::command]
+pub async fn clear_token(
+    state: State<'_, Arc<AppState>>,
+    account: Option<String>,
+) -> Result<(), String> {
+    let mut settings = account_settings(&state)?;
+    let account = account.unwrap_or_else(|| settings.active.clone());
+    let was_active = account == settings.active;
+
     state
         .token_store
-        .set_token(clean)
-        .map_err(map_token_store_error)
+        .clear_token(&account)
+        .map_err(|e| e.message_for_user())?;
+    settings.remove(&account);
+    state
+        .settings_store
+        .set(ACCOUNTS_KEY, &settings)
+        .map_err(|e| e.to_string())?;
+    if was_active {
+        clear_account_caches(&state)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_accounts(state: State<'_, Arc<AppState>>) -> Result<AccountList, String> {
+    let settings = account_settings(&state)?;
+    let mut accounts = settings.accounts.clone();
+    if !accounts
+        .iter()
+        .any(|account| account.name == DEFAULT_ACCOUNT)
+    {
+        accounts.insert(
+            0,
+            Account {
+                name: DEFAULT_ACCOUNT.to_string(),
+                username: String::new(),
+                backend: None,
+            },
+        );
+    }
+
+    let mut list = Vec::new();
+    for account in accounts {
+        let token = match state.token_store.get_token(&account.name) {
+            Err(CredentialError::Locked) => None,
+            other => other.map_err(|e| e.message_for_user())?,
+        };
+        // Tokens saved before accounts existed have no recorded username.
+        let username = match (&token, account.username.is_empty()) {
+            (Some(token), true) => token.split(':').next().unwrap_or_default().to_string(),
+            _ => account.username,
+        };
+        list.push(AccountInfo {
+            name: account.name,
+            username,
+            token_configured: token.is_some(),
+            backend: account.backend,
+        });
+    }
+    Ok(AccountList {
+        active: settings.active,
+        accounts: list,
+    })
+}
+
+/// Makes `name` the account new bookmarks and lookups use. Queued items keep
+/// the account they were created for.
+#[tauri::command]
+pub async fn set_active_account(
+    state: State<'_, Arc<AppState>>,
+    name: String,
+) -> Result<AccountList, String> {
+    let mut settings = account_settings(&state)?;
+    if settings.active != name {
+        settings.select(&name).map_err(|e| e.to_string())?;
+        state
+            .settings_store
+            .set(ACCOUNTS_KEY, &settings)
+            .map_err(|e| e.to_string())?;
+        clear_account_caches(&state)?;
+    }
+    list_accounts(state).await
 }
 
+/// Points `account` at a Pinboard-compatible server, or back at Pinboard
+/// with `None`. The account is created if it does not exist yet, so the
+/// backend can be set before its token is saved and verified.
 #[tauri::command]
-pub async fn clear_token(state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    state.token_store.clear_token().map_err(map_token_store_error)
+pub async fn save_account_backend(
+    state: State<'_, Arc<AppState>>,
+    account: String,
+    backend: Option<AccountBackend>,
+) -> Result<AccountList, String> {
+    let account = validate_account_name(&account).map_err(|e| e.to_string())?;
+    let backend = backend
+        .map(|backend| validate_backend(&backend))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let mut settings = account_settings(&state)?;
+    settings.set_backend(&account, backend);
+    state
+        .settings_store
+        .set(ACCOUNTS_KEY, &settings)
+        .map_err(|e| e.to_string())?;
+    // Queued items may have failed against the old server.
+    state.queue_store.resume(&account);
+    if account == settings.active {
+        clear_account_caches(&state)?;
+    }
+    list_accounts(state).await
+}
+
+#[tauri::command]
+pub async fn get_credential_settings(
+    state: State<'_, Arc<AppState>>,
+) -> Result<CredentialStatus, String> {
+    Ok(state.credentials.status())
+}
+
+/// Chooses where tokens are read from and saved to. Tokens are not moved
+/// between backends; save them again after switching.
+#[tauri::command]
+pub async fn save_credential_settings(
+    state: State<'_, Arc<AppState>>,
+    settings: CredentialSettings,
+) -> Result<CredentialStatus, String> {
+    let settings = settings.validated().map_err(|e| e.message_for_user())?;
+
+    state
+        .settings_store
+        .set(CREDENTIALS_KEY, &settings)
+        .map_err(|e| e.to_string())?;
+    state.credentials.configure(settings);
+    state.queue_store.resume_all();
+    Ok(state.credentials.status())
+}
+
+/// Decrypts the credential file for this session, or sets the passphrase
+/// for a new one.
+#[tauri::command]
+pub async fn unlock_credentials(
+    state: State<'_, Arc<AppState>>,
+    passphrase: String,
+) -> Result<CredentialStatus, String> {
+    state
+        .credentials
+        .unlock(&passphrase)
+        .map_err(|e| e.message_for_user())?;
+    state.queue_store.resume_all();
+    Ok(state.credentials.status())
+}
+
+// The cached tags and collection belong to whichever account was active before.
+fn clear_account_caches(state: &AppState) -> Result<(), String> {
+    state.tag_cache.clear().map_err(|e| e.to_string())?;
+    state.library.clear().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -105,33 +424,28 @@ pub async fn fetch_tag_suggestions(
     state: State<'_, Arc<AppState>>,
     url: String,
 ) -> Result<TagSuggestions, String> {
-    let token = state
-        .token_store
-        .get_token()
-        .map_err(map_token_store_error)?
-        .ok_or_else(|| "Pinboard token is not set".to_string())?;
+    let Credentials { token, client, .. } = active_credentials(&state)?;
 
     let normalized = normalize_url(&url).ok_or_else(|| "Invalid URL".to_string())?;
-    state
-        .pinboard
+    let suggestions = client
         .suggest_tags(&token, &normalized)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    // Suggestions mix in community data and feed "Add all", so keep them public.
+    Ok(TagSuggestions {
+        popular: strip_private_tags(&suggestions.popular),
+        recommended: strip_private_tags(&suggestions.recommended),
+    })
 }
 
 #[tauri::command]
-pub async fn fetch_user_tags(state: State<'_, Arc<AppState>>) -> Result<Vec<String>, String> {
-    let token = state
-        .token_store
-        .get_token()
-        .map_err(map_token_store_error)?
-        .ok_or_else(|| "Pinboard token is not set".to_string())?;
-
-    state
-        .pinboard
-        .get_user_tags(&token)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn fetch_user_tags(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<String>, String> {
+    let tags = cached_user_tags(&app, state.inner()).await?;
+    Ok(tags.into_iter().map(|user_tag| user_tag.tag).collect())
 }
 
 #[tauri::command]
@@ -161,34 +475,123 @@ pub async fn check_duplicate(
 }
 
 #[tauri::command]
-pub async fn submit_bookmark(
+pub async fn submit_bookmark<R: Runtime>(
+    app: AppHandle<R>,
     state: State<'_, Arc<AppState>>,
     payload: BookmarkPayload,
 ) -> Result<SubmitResult, String> {
-    let token = state
-        .token_store
-        .get_token()
-        .map_err(map_token_store_error)?
-        .ok_or_else(|| "Pinboard token is not set".to_string())?;
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(&state)?;
 
     let mut clean_payload = payload;
     clean_payload.url =
         normalize_url(&clean_payload.url).ok_or_else(|| "Invalid URL".to_string())?;
-    clean_payload.tags = parse_tags(&clean_payload.tags.join(" "));
 
-    match state.pinboard.add_bookmark(&token, &clean_payload).await {
-        Ok(_) => Ok(SubmitResult {
-            status: "sent".to_string(),
-            message: "Saved to Pinboard".to_string(),
+    let bundles = state
+        .settings_store
+        .get::<Vec<TagBundle>>(TAG_BUNDLES_KEY)
+        .map_err(|e| e.to_string())?;
+    clean_payload.tags =
+        parse_tag_list(&expand_bundles(&tag_strings(&clean_payload.tags), &bundles).tags);
+
+    let rules = state
+        .settings_store
+        .get::<Vec<DomainRule>>(DOMAIN_RULES_KEY)
+        .map_err(|e| e.to_string())?;
+    let outcome = evaluate_rules(&rules, &clean_payload.url, &clean_payload.title);
+    apply_rule_outcome(&mut clean_payload, &outcome);
+
+    let normalization = state
+        .settings_store
+        .get::<TagNormalization>(TAG_NORMALIZATION_KEY)
+        .map_err(|e| e.to_string())?;
+    clean_payload.tags = normalize_tags(&tag_strings(&clean_payload.tags), &normalization);
+
+    let field_errors = validate_payload(&clean_payload);
+    if !field_errors.is_empty() {
+        return Ok(SubmitResult {
+            status: "invalid".to_string(),
+            message: "Fix the highlighted fields before saving".to_string(),
+            queued: false,
+            field_errors,
+            conflict: None,
+        });
+    }
+
+    match client.add_bookmark(&token, &clean_payload).await {
+        Ok(written) => {
+            record_tag_usage(&state, &account, &written);
+            let _ = state.undo.mark_submitted(&account, &clean_payload.url);
+            Ok(SubmitResult {
+                status: "sent".to_string(),
+                message: "Saved to Pinboard".to_string(),
+                queued: false,
+                field_errors: Vec::new(),
+                conflict: None,
+            })
+        }
+        Err(PinboardError::Conflict { remote }) => {
+            let conflict = clean_payload
+                .base
+                .as_deref()
+                .map(|base| build_conflict(base, remote.as_deref(), &clean_payload));
+            Ok(SubmitResult {
+                status: "conflict".to_string(),
+                message: PinboardError::Conflict { remote }.message_for_user(),
+                queued: false,
+                field_errors: Vec::new(),
+                conflict,
+            })
+        }
+        Err(PinboardError::InvalidMerge { field_errors }) => Ok(SubmitResult {
+            status: "invalid".to_string(),
+            message: "Merged with the saved bookmark, this is over Pinboard's limits".to_string(),
             queued: false,
+            field_errors,
+            conflict: None,
         }),
+        Err(err @ PinboardError::Unauthorized { .. }) => {
+            // Kept in the queue, which stays paused until a new token is saved.
+            state
+                .queue_store
+                .enqueue(
+                    &account,
+                    &QueueOperation::AddBookmark {
+                        payload: clean_payload.clone(),
+                    },
+                    &err.message_for_user(),
+                    0,
+                )
+                .map_err(|e| e.to_string())?;
+            record_tag_usage(&state, &account, &clean_payload);
+            pause_for_auth(&app, &state, &account, err.message_for_user());
+            Ok(SubmitResult {
+                status: "queued".to_string(),
+                message: "Pinboard rejected the API token. Queued until you enter a new one."
+                    .to_string(),
+                queued: true,
+                field_errors: Vec::new(),
+                conflict: None,
+            })
+        }
         Err(err) => {
             if err.is_retryable() {
                 let retry_after = err.retry_after_secs().unwrap_or(15);
                 state
                     .queue_store
-                    .enqueue(&clean_payload, &err.message_for_user(), retry_after)
+                    .enqueue(
+                        &account,
+                        &QueueOperation::AddBookmark {
+                            payload: clean_payload.clone(),
+                        },
+                        &err.message_for_user(),
+                        retry_after,
+                    )
                     .map_err(|e| e.to_string())?;
+                record_tag_usage(&state, &account, &clean_payload);
                 Ok(SubmitResult {
                     status: "queued".to_string(),
                     message: format!(
@@ -196,6 +599,8 @@ pub async fn submit_bookmark(
                         err.message_for_user()
                     ),
                     queued: true,
+                    field_errors: Vec::new(),
+                    conflict: None,
                 })
             } else {
                 Err(format!(
@@ -207,6 +612,646 @@ pub async fn submit_bookmark(
     }
 }
 
+#[tauri::command]
+pub async fn rank_tag_suggestions(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    url: String,
+    title: String,
+    entered: Vec<String>,
+    suggestions: Option<TagSuggestions>,
+    include_private: Option<bool>,
+) -> Result<Vec<RankedTag>, String> {
+    let user_tags = cached_user_tags(&app, state.inner()).await?;
+    let active = account_settings(&state)?.active;
+    let history = state
+        .tag_history
+        .recent(&active, TAG_HISTORY_LIMIT)
+        .map_err(|e| e.to_string())?;
+    let host = url_host(&url);
+
+    let context = RankingContext {
+        entered: &entered,
+        host: host.as_deref(),
+        title: &title,
+        now: now_unix(),
+        include_private: include_private.unwrap_or(false),
+    };
+    Ok(rank_tags(
+        &context,
+        &user_tags,
+        &history,
+        suggestions.as_ref(),
+        RANKED_TAG_LIMIT,
+    ))
+}
+
+#[tauri::command]
+pub async fn rename_user_tag<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+    from: String,
+    to: String,
+    dry_run: bool,
+) -> Result<TagMutationResult, String> {
+    let operations = rename_operations(&[from], &to)?;
+    run_tag_mutations(&app, state.inner(), operations, dry_run).await
+}
+
+#[tauri::command]
+pub async fn merge_user_tags<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+    sources: Vec<String>,
+    target: String,
+    dry_run: bool,
+) -> Result<TagMutationResult, String> {
+    let operations = rename_operations(&sources, &target)?;
+    run_tag_mutations(&app, state.inner(), operations, dry_run).await
+}
+
+#[tauri::command]
+pub async fn delete_user_tag<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+    tag: String,
+    dry_run: bool,
+) -> Result<TagMutationResult, String> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err("Tag is required".to_string());
+    }
+    run_tag_mutations(
+        &app,
+        state.inner(),
+        vec![QueueOperation::DeleteTag { tag }],
+        dry_run,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn search_bookmarks(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<ExistingBookmark>, String> {
+    let parsed = SearchQuery::parse(&query);
+    search_library(
+        &app,
+        state.inner(),
+        &parsed,
+        limit.unwrap_or(SEARCH_RESULT_LIMIT),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn sync_bookmarks(state: State<'_, Arc<AppState>>) -> Result<usize, String> {
+    sync_library(state.inner()).await
+}
+
+/// Parses an export file into a resumable import job and starts feeding it
+/// into the offline queue. The format is detected when not given.
+#[tauri::command]
+pub async fn import_bookmarks(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    format: Option<ImportFormat>,
+) -> Result<ImportJob, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Could not read {path}: {e}"))?;
+    let format = format.unwrap_or_else(|| detect_format(&content));
+    let payloads = parse_import(&content, format).map_err(|e| e.to_string())?;
+    let source = PathBuf::from(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or(path);
+
+    let job = state
+        .imports
+        .create(
+            &account_settings(&state)?.active,
+            &source,
+            format,
+            &payloads,
+        )
+        .map_err(|e| e.to_string())?;
+    spawn_pending_imports(app, state.inner().clone());
+    Ok(job)
+}
+
+/// Writes the local collection, or the bookmarks matching `query`, to `path`.
+/// A never-synced collection is downloaded first. Returns the number written.
+#[tauri::command]
+pub async fn export_bookmarks(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    format: ExportFormat,
+    query: Option<String>,
+) -> Result<usize, String> {
+    let never_synced = state
+        .library
+        .fetched_at()
+        .map_err(|e| e.to_string())?
+        .is_none();
+    if never_synced {
+        sync_library(state.inner()).await?;
+    }
+
+    let query = SearchQuery::parse(query.as_deref().unwrap_or(""));
+    let total = state.library.count().map_err(|e| e.to_string())?;
+    let bookmarks = state
+        .library
+        .search(&query, total.max(0) as usize)
+        .map_err(|e| e.to_string())?;
+
+    let mut path = PathBuf::from(path);
+    if path.extension().is_none() {
+        path.set_extension(format.extension());
+    }
+    fs::write(&path, render_export(&bookmarks, format))
+        .map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    Ok(bookmarks.len())
+}
+
+#[tauri::command]
+pub async fn import_jobs(state: State<'_, Arc<AppState>>) -> Result<Vec<ImportJob>, String> {
+    state
+        .imports
+        .list(IMPORT_JOB_LIMIT)
+        .map_err(|e| e.to_string())
+}
+
+/// Restarts a failed import from the last saved position.
+#[tauri::command]
+pub async fn resume_import(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+    id: i64,
+) -> Result<ImportJob, String> {
+    state.imports.reopen(id).map_err(|e| e.to_string())?;
+    spawn_pending_imports(app, state.inner().clone());
+    state
+        .imports
+        .get(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Import job not found".to_string())
+}
+
+/// Writes queue items, settings and tag data to `path`. With a passphrase
+/// every account's saved Pinboard token is included, encrypted with it.
+#[tauri::command]
+pub async fn create_backup(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    passphrase: Option<String>,
+) -> Result<BackupSummary, String> {
+    let mut archive = state.backups.snapshot().map_err(|e| e.to_string())?;
+    if let Some(passphrase) = passphrase.filter(|p| !p.is_empty()) {
+        for account in account_settings(&state)?.names() {
+            if let Some(token) = state
+                .token_store
+                .get_token(&account)
+                .map_err(|e| e.message_for_user())?
+            {
+                let sealed = sealed::seal(&passphrase, &token).map_err(|e| e.to_string())?;
+                archive.tokens.insert(account, sealed);
+            }
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())?;
+    let mut path = PathBuf::from(path);
+    if path.extension().is_none() {
+        path.set_extension("json");
+    }
+    fs::write(&path, json).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+    Ok(archive.summary())
+}
+
+/// Restores a backup written by `create_backup`. Nothing is changed unless
+/// the archive validates and, if it carries tokens, the passphrase opens them.
+#[tauri::command]
+pub async fn restore_backup(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+    mode: RestoreMode,
+    passphrase: Option<String>,
+) -> Result<BackupSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Could not read {path}: {e}"))?;
+    let archive = serde_json::from_str::<BackupArchive>(&content)
+        .map_err(|_| BackupError::NotABackup.to_string())?;
+    validate_archive(&archive).map_err(|e| e.to_string())?;
+
+    let passphrase = passphrase.filter(|p| !p.is_empty());
+    let open = |sealed| match &passphrase {
+        Some(passphrase) => sealed::open(passphrase, sealed).map_err(|e| e.to_string()),
+        None => Err(BackupError::PassphraseRequired.to_string()),
+    };
+    let legacy_token = archive.token.as_ref().map(open).transpose()?;
+    let tokens = archive
+        .tokens
+        .iter()
+        .map(|(account, sealed)| Ok((account.clone(), open(sealed)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut summary = state
+        .backups
+        .restore(&archive, mode)
+        .map_err(|e| e.to_string())?;
+    state.credentials.configure(
+        state
+            .settings_store
+            .get(CREDENTIALS_KEY)
+            .map_err(|e| e.to_string())?,
+    );
+    // A token from an older archive goes to whichever account is active once
+    // the settings are restored.
+    let active = account_settings(&state)?.active;
+    let tokens = tokens
+        .into_iter()
+        .chain(legacy_token.map(|token| (active.clone(), token)));
+    for (account, token) in tokens {
+        state
+            .token_store
+            .set_token(&account, &token)
+            .map_err(|e| e.message_for_user())?;
+        state.queue_store.resume(&account);
+        if account == active {
+            // The restored tag data belongs to this token; the collection may not.
+            state.library.clear().map_err(|e| e.to_string())?;
+        }
+        summary.tokens.push(account);
+    }
+    Ok(summary)
+}
+
+#[tauri::command]
+pub async fn recent_bookmarks(
+    state: State<'_, Arc<AppState>>,
+    count: Option<usize>,
+) -> Result<BookmarkList, String> {
+    let Credentials { token, client, .. } = active_credentials(&state)?;
+    let count = count.unwrap_or(RECENT_BOOKMARK_LIMIT);
+
+    match client.get_recent_bookmarks(&token, count).await {
+        Ok(bookmarks) => {
+            for bookmark in &bookmarks {
+                let _ = state.library.upsert(bookmark);
+            }
+            Ok(BookmarkList {
+                bookmarks,
+                offline: false,
+                message: None,
+            })
+        }
+        Err(err) if err.is_retryable() => {
+            let bookmarks = state
+                .library
+                .search(&SearchQuery::default(), count)
+                .map_err(|e| e.to_string())?;
+            Ok(BookmarkList {
+                bookmarks,
+                offline: true,
+                message: Some(format!(
+                    "Pinboard unavailable, showing saved copy: {}",
+                    err.message_for_user()
+                )),
+            })
+        }
+        Err(err) => Err(format!(
+            "Pinboard rejected request: {}",
+            err.message_for_user()
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn read_later_inbox(
+    app: AppHandle,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ExistingBookmark>, String> {
+    let query = SearchQuery {
+        read_later: Some(true),
+        ..SearchQuery::default()
+    };
+    search_library(&app, state.inner(), &query, INBOX_LIMIT).await
+}
+
+#[tauri::command]
+pub async fn mark_bookmark_read<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+    url: String,
+) -> Result<SubmitResult, String> {
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(&state)?;
+
+    let url = normalize_url(&url).ok_or_else(|| "Invalid URL".to_string())?;
+    let existing = match client.get_existing_bookmark(&token, &url).await {
+        Ok(Some(bookmark)) => bookmark,
+        Ok(None) => return Err("Bookmark not found on Pinboard".to_string()),
+        // Fall back to the local copy; the queued write still checks it
+        // against Pinboard before replacing anything.
+        Err(err) if err.is_retryable() || matches!(err, PinboardError::Unauthorized { .. }) => {
+            state
+                .library
+                .get(&url)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| err.message_for_user())?
+        }
+        Err(err) => return Err(err.message_for_user()),
+    };
+    let read = ExistingBookmark {
+        read_later: false,
+        ..existing.clone()
+    };
+    let payload = BookmarkPayload {
+        base: Some(Box::new(existing)),
+        ..read.update_payload()
+    };
+
+    match client.add_bookmark(&token, &payload).await {
+        Ok(_) => {
+            let _ = state.library.upsert(&read);
+            Ok(SubmitResult {
+                status: "sent".to_string(),
+                message: "Marked as read".to_string(),
+                queued: false,
+                field_errors: Vec::new(),
+                conflict: None,
+            })
+        }
+        Err(PinboardError::Conflict { remote }) => {
+            let conflict = payload
+                .base
+                .as_deref()
+                .map(|base| build_conflict(base, remote.as_deref(), &payload));
+            Ok(SubmitResult {
+                status: "conflict".to_string(),
+                message: PinboardError::Conflict { remote }.message_for_user(),
+                queued: false,
+                field_errors: Vec::new(),
+                conflict,
+            })
+        }
+        Err(err) if err.is_retryable() => {
+            let retry_after = err.retry_after_secs().unwrap_or(15);
+            state
+                .queue_store
+                .enqueue(
+                    &account,
+                    &QueueOperation::AddBookmark { payload },
+                    &err.message_for_user(),
+                    retry_after,
+                )
+                .map_err(|e| e.to_string())?;
+            let _ = state.library.upsert(&read);
+            Ok(SubmitResult {
+                status: "queued".to_string(),
+                message: format!(
+                    "Pinboard unavailable right now. Queued for retry: {}",
+                    err.message_for_user()
+                ),
+                queued: true,
+                field_errors: Vec::new(),
+                conflict: None,
+            })
+        }
+        Err(err @ PinboardError::Unauthorized { .. }) => {
+            state
+                .queue_store
+                .enqueue(
+                    &account,
+                    &QueueOperation::AddBookmark { payload },
+                    &err.message_for_user(),
+                    0,
+                )
+                .map_err(|e| e.to_string())?;
+            let _ = state.library.upsert(&read);
+            pause_for_auth(&app, &state, &account, err.message_for_user());
+            Ok(SubmitResult {
+                status: "queued".to_string(),
+                message: "Pinboard rejected the API token. Queued until you enter a new one."
+                    .to_string(),
+                queued: true,
+                field_errors: Vec::new(),
+                conflict: None,
+            })
+        }
+        Err(err) => Err(format!(
+            "Pinboard rejected bookmark: {}",
+            err.message_for_user()
+        )),
+    }
+}
+
+/// Deletes a bookmark in two steps: without `confirm` it only looks the
+/// bookmark up and returns it for the UI to confirm.
+#[tauri::command]
+pub async fn delete_bookmark<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+    url: String,
+    confirm: bool,
+) -> Result<DeleteBookmarkResult, String> {
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(&state)?;
+    // Same form as submitted and cached, so the lookup, the delete and the
+    // local removal all hit the stored bookmark.
+    let url = normalize_url(&url).ok_or_else(|| "Invalid URL".to_string())?;
+
+    if !confirm {
+        let bookmark = match state.library.get(&url).map_err(|e| e.to_string())? {
+            Some(bookmark) => Some(bookmark),
+            None => client
+                .get_existing_bookmark(&token, &url)
+                .await
+                .map_err(|e| e.message_for_user())?,
+        };
+        let message = match &bookmark {
+            Some(bookmark) => format!("Delete \"{}\" from Pinboard?", bookmark.title),
+            None => "Bookmark not found on Pinboard".to_string(),
+        };
+        return Ok(DeleteBookmarkResult {
+            status: "confirm".to_string(),
+            message,
+            queued: false,
+            bookmark,
+        });
+    }
+
+    match client.delete_bookmark(&token, &url).await {
+        Ok(_) => {
+            let _ = state.library.remove(&url);
+            Ok(DeleteBookmarkResult {
+                status: "sent".to_string(),
+                message: "Deleted from Pinboard".to_string(),
+                queued: false,
+                bookmark: None,
+            })
+        }
+        Err(err) if err.is_retryable() => {
+            let retry_after = err.retry_after_secs().unwrap_or(15);
+            state
+                .queue_store
+                .enqueue(
+                    &account,
+                    &QueueOperation::DeleteBookmark { url: url.clone() },
+                    &err.message_for_user(),
+                    retry_after,
+                )
+                .map_err(|e| e.to_string())?;
+            let _ = state.library.remove(&url);
+            Ok(DeleteBookmarkResult {
+                status: "queued".to_string(),
+                message: format!(
+                    "Pinboard unavailable right now. Delete queued for retry: {}",
+                    err.message_for_user()
+                ),
+                queued: true,
+                bookmark: None,
+            })
+        }
+        Err(err @ PinboardError::Unauthorized { .. }) => {
+            state
+                .queue_store
+                .enqueue(
+                    &account,
+                    &QueueOperation::DeleteBookmark { url: url.clone() },
+                    &err.message_for_user(),
+                    0,
+                )
+                .map_err(|e| e.to_string())?;
+            let _ = state.library.remove(&url);
+            pause_for_auth(&app, &state, &account, err.message_for_user());
+            Ok(DeleteBookmarkResult {
+                status: "queued".to_string(),
+                message:
+                    "Pinboard rejected the API token. Delete queued until you enter a new one."
+                        .to_string(),
+                queued: true,
+                bookmark: None,
+            })
+        }
+        Err(err) => Err(format!(
+            "Pinboard rejected delete: {}",
+            err.message_for_user()
+        )),
+    }
+}
+
+#[tauri::command]
+pub async fn get_undo_status(state: State<'_, Arc<AppState>>) -> Result<Option<UndoInfo>, String> {
+    let entry = state.undo.last_submission().map_err(|e| e.to_string())?;
+    Ok(entry.map(|entry| UndoInfo {
+        action: if entry.previous.is_some() {
+            "restore".to_string()
+        } else {
+            "delete".to_string()
+        },
+        url: entry.url,
+        expires_at: entry.submitted_at + UNDO_WINDOW_SECS,
+    }))
+}
+
+/// Reverts the last sent submission: a newly created bookmark is deleted, an
+/// updated one is re-posted with the fields `check_duplicate` saw before.
+#[tauri::command]
+pub async fn undo_last_submission<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<SubmitResult, String> {
+    let entry = state
+        .undo
+        .last_submission()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Nothing to undo".to_string())?;
+    // Undo goes to the account the bookmark was saved to, even if the user
+    // has switched since.
+    let settings = account_settings(&state)?;
+    let Credentials {
+        account,
+        token,
+        client,
+    } = credentials_for(&state, &settings, &entry.account)?;
+
+    let operation = match &entry.previous {
+        Some(previous) => QueueOperation::AddBookmark {
+            payload: previous.update_payload(),
+        },
+        None => QueueOperation::DeleteBookmark {
+            url: entry.url.clone(),
+        },
+    };
+
+    let result = match execute_operation(&client, &token, &operation).await {
+        Ok(_) => SubmitResult {
+            status: "sent".to_string(),
+            message: "Last save undone".to_string(),
+            queued: false,
+            field_errors: Vec::new(),
+            conflict: None,
+        },
+        Err(err) if err.is_retryable() => {
+            let retry_after = err.retry_after_secs().unwrap_or(15);
+            state
+                .queue_store
+                .enqueue(&account, &operation, &err.message_for_user(), retry_after)
+                .map_err(|e| e.to_string())?;
+            SubmitResult {
+                status: "queued".to_string(),
+                message: format!(
+                    "Pinboard unavailable right now. Undo queued for retry: {}",
+                    err.message_for_user()
+                ),
+                queued: true,
+                field_errors: Vec::new(),
+                conflict: None,
+            }
+        }
+        Err(err @ PinboardError::Unauthorized { .. }) => {
+            state
+                .queue_store
+                .enqueue(&account, &operation, &err.message_for_user(), 0)
+                .map_err(|e| e.to_string())?;
+            pause_for_auth(&app, &state, &account, err.message_for_user());
+            SubmitResult {
+                status: "queued".to_string(),
+                message: "Pinboard rejected the API token. Undo queued until you enter a new one."
+                    .to_string(),
+                queued: true,
+                field_errors: Vec::new(),
+                conflict: None,
+            }
+        }
+        Err(err) => {
+            return Err(format!(
+                "Pinboard rejected undo: {}",
+                err.message_for_user()
+            ))
+        }
+    };
+
+    // The local collection mirrors the active account only.
+    if account == settings.active {
+        let _ = match &entry.previous {
+            Some(previous) => state.library.upsert(previous),
+            None => state.library.remove(&entry.url),
+        };
+    }
+    state.undo.remove(&entry.url).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
 #[tauri::command]
 pub async fn queue_list(
     state: State<'_, Arc<AppState>>,
@@ -215,10 +1260,12 @@ pub async fn queue_list(
 }
 
 #[tauri::command]
-pub async fn queue_retry_now(
-    app: AppHandle,
+pub async fn queue_retry_now<R: Runtime>(
+    app: AppHandle<R>,
     state: State<'_, Arc<AppState>>,
 ) -> Result<QueueRetryResult, String> {
+    // A manual retry also gives a paused queue another try with the stored token.
+    state.queue_store.resume_all();
     let sent = process_due_items(&app, state.inner(), 25).await?;
     let remaining = state
         .queue_store
@@ -228,6 +1275,206 @@ pub async fn queue_retry_now(
     Ok(QueueRetryResult { sent, remaining })
 }
 
+#[tauri::command]
+pub async fn queue_conflicts(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<QueuedConflict>, String> {
+    let settings = account_settings(&state)?;
+    let items = state.queue_store.conflicts().map_err(|e| e.to_string())?;
+
+    // Each item is compared against the account it was queued for.
+    let mut credentials = HashMap::new();
+    let mut conflicts = Vec::new();
+    for item in items {
+        let QueueOperation::AddBookmark { payload } = item.operation else {
+            continue;
+        };
+        let Some(base) = payload.base.as_deref() else {
+            continue;
+        };
+        if !credentials.contains_key(&item.account) {
+            let found = credentials_for(&state, &settings, &item.account);
+            credentials.insert(item.account.clone(), found);
+        }
+        // One unreadable remote doesn't hide the other conflicts.
+        let remote = match &credentials[&item.account] {
+            Ok(Credentials { token, client, .. }) => client
+                .get_existing_bookmark(token, &payload.url)
+                .await
+                .map_err(|e| e.message_for_user()),
+            Err(err) => Err(err.clone()),
+        };
+        let (remote, error) = match remote {
+            Ok(remote) => (remote, None),
+            Err(err) => (None, Some(err)),
+        };
+        conflicts.push(QueuedConflict {
+            id: item.id,
+            conflict: build_conflict(base, remote.as_ref(), &payload),
+            error,
+        });
+    }
+    Ok(conflicts)
+}
+
+/// Settles a parked update. Keeping the local edit re-reads the bookmark with
+/// the item's own account and re-queues it based on that version, provided it
+/// is still the one the user reviewed; otherwise the queued edit is dropped.
+#[tauri::command]
+pub async fn resolve_queue_conflict(
+    state: State<'_, Arc<AppState>>,
+    id: i64,
+    keep_local: bool,
+    remote: Option<ExistingBookmark>,
+) -> Result<(), String> {
+    let item = state
+        .queue_store
+        .conflicts()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| "Queued conflict not found".to_string())?;
+
+    match item.operation {
+        QueueOperation::AddBookmark { mut payload } if keep_local => {
+            let settings = account_settings(&state)?;
+            let Credentials { token, client, .. } =
+                credentials_for(&state, &settings, &item.account)?;
+            let current = client
+                .get_existing_bookmark(&token, &payload.url)
+                .await
+                .map_err(|e| e.message_for_user())?;
+            let changed_again = match (&remote, &current) {
+                (Some(reviewed), Some(current)) => changed_since(reviewed, current),
+                (None, None) => false,
+                _ => true,
+            };
+            if changed_again {
+                return Err(
+                    "The bookmark changed again on Pinboard; review the conflict again".to_string(),
+                );
+            }
+            payload.base = current.map(Box::new);
+            state
+                .queue_store
+                .requeue(id, &QueueOperation::AddBookmark { payload })
+                .map_err(|e| e.to_string())
+        }
+        _ => state
+            .queue_store
+            .discard_conflict(id)
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_domain_rules(state: State<'_, Arc<AppState>>) -> Result<Vec<DomainRule>, String> {
+    state
+        .settings_store
+        .get(DOMAIN_RULES_KEY)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_domain_rules(
+    state: State<'_, Arc<AppState>>,
+    rules: Vec<DomainRule>,
+) -> Result<(), String> {
+    validate_rules(&rules).map_err(|e| e.to_string())?;
+    state
+        .settings_store
+        .set(DOMAIN_RULES_KEY, &rules)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn evaluate_domain_rules(
+    state: State<'_, Arc<AppState>>,
+    url: String,
+    title: String,
+) -> Result<RuleOutcome, String> {
+    let rules = state
+        .settings_store
+        .get::<Vec<DomainRule>>(DOMAIN_RULES_KEY)
+        .map_err(|e| e.to_string())?;
+    Ok(evaluate_rules(&rules, &url, &title))
+}
+
+#[tauri::command]
+pub async fn get_tag_normalization(
+    state: State<'_, Arc<AppState>>,
+) -> Result<TagNormalization, String> {
+    state
+        .settings_store
+        .get(TAG_NORMALIZATION_KEY)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_tag_normalization(
+    state: State<'_, Arc<AppState>>,
+    settings: TagNormalization,
+) -> Result<(), String> {
+    settings.validate().map_err(|e| e.to_string())?;
+    state
+        .settings_store
+        .set(TAG_NORMALIZATION_KEY, &settings)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preview_tag_normalization(
+    state: State<'_, Arc<AppState>>,
+    tags: Vec<String>,
+) -> Result<TagPreview, String> {
+    let bundles = state
+        .settings_store
+        .get::<Vec<TagBundle>>(TAG_BUNDLES_KEY)
+        .map_err(|e| e.to_string())?;
+    let expansion = expand_bundles(&tags, &bundles);
+    let normalization = state
+        .settings_store
+        .get::<TagNormalization>(TAG_NORMALIZATION_KEY)
+        .map_err(|e| e.to_string())?;
+
+    let mut preview = preview_tags(&expansion.tags, &normalization);
+    preview.rewrites.splice(0..0, expansion.expanded);
+    Ok(preview)
+}
+
+#[tauri::command]
+pub async fn get_tag_bundles(state: State<'_, Arc<AppState>>) -> Result<Vec<TagBundle>, String> {
+    state
+        .settings_store
+        .get(TAG_BUNDLES_KEY)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn save_tag_bundles(
+    state: State<'_, Arc<AppState>>,
+    bundles: Vec<TagBundle>,
+) -> Result<(), String> {
+    validate_bundles(&bundles).map_err(|e| e.to_string())?;
+    state
+        .settings_store
+        .set(TAG_BUNDLES_KEY, &bundles)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn expand_tag_bundles(
+    state: State<'_, Arc<AppState>>,
+    tags: Vec<String>,
+) -> Result<BundleExpansion, String> {
+    let bundles = state
+        .settings_store
+        .get::<Vec<TagBundle>>(TAG_BUNDLES_KEY)
+        .map_err(|e| e.to_string())?;
+    Ok(expand_bundles(&tags, &bundles))
+}
+
 #[tauri::command]
 pub async fn get_omarchy_theme() -> Result<Option<OmarchyTheme>, String> {
     let config_root = env::var("XDG_CONFIG_HOME")
@@ -264,6 +1511,167 @@ pub async fn get_omarchy_theme() -> Result<Option<OmarchyTheme>, String> {
     Ok(Some(OmarchyTheme { name, colors }))
 }
 
+fn rename_operations(sources: &[String], target: &str) -> Result<Vec<QueueOperation>, String> {
+    let target = target.trim();
+    if let Some(message) = validate_tag(target) {
+        return Err(message);
+    }
+
+    let mut seen = Vec::<String>::new();
+    let operations = sources
+        .iter()
+        .map(|source| source.trim())
+        .filter(|source| !source.is_empty() && *source != target)
+        .filter(|source| {
+            let key = source.to_lowercase();
+            let fresh = !seen.contains(&key);
+            seen.push(key);
+            fresh
+        })
+        .map(|source| QueueOperation::RenameTag {
+            from: source.to_string(),
+            to: target.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    if operations.is_empty() {
+        return Err("Pick at least one tag different from the target".to_string());
+    }
+    Ok(operations)
+}
+
+/// Runs tag mutations in order. A dry run only reports how many cached
+/// bookmarks carry the affected tags. On a retryable failure the failed
+/// mutation and everything after it is queued, preserving order; a rejected
+/// token queues them too and pauses the account.
+async fn run_tag_mutations<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &Arc<AppState>,
+    operations: Vec<QueueOperation>,
+    dry_run: bool,
+) -> Result<TagMutationResult, String> {
+    let cached = state.tag_cache.list().map_err(|e| e.to_string())?;
+    let affected = operations
+        .iter()
+        .filter_map(|operation| match operation {
+            QueueOperation::RenameTag { from, .. } => Some(from),
+            QueueOperation::DeleteTag { tag } => Some(tag),
+            QueueOperation::AddBookmark { .. } | QueueOperation::DeleteBookmark { .. } => None,
+        })
+        .flat_map(|source| {
+            cached
+                .iter()
+                .filter(move |cached_tag| cached_tag.tag.eq_ignore_ascii_case(source))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let affected_bookmarks = affected.iter().map(|tag| tag.count).sum::<i64>();
+
+    if dry_run {
+        return Ok(TagMutationResult {
+            status: "preview".to_string(),
+            message: format!("{affected_bookmarks} cached bookmark(s) would change"),
+            queued: false,
+            affected,
+            affected_bookmarks,
+        });
+    }
+
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(state)?;
+
+    let mut queued_from = None;
+    for (index, operation) in operations.iter().enumerate() {
+        match execute_operation(&client, &token, operation).await {
+            Ok(()) => apply_tag_mutation_locally(state, operation),
+            Err(err) if err.is_retryable() || matches!(err, PinboardError::Unauthorized { .. }) => {
+                let retry_after = err.retry_after_secs().unwrap_or(15);
+                for pending in &operations[index..] {
+                    state
+                        .queue_store
+                        .enqueue(&account, pending, &err.message_for_user(), retry_after)
+                        .map_err(|e| e.to_string())?;
+                    apply_tag_mutation_locally(state, pending);
+                }
+                if matches!(err, PinboardError::Unauthorized { .. }) {
+                    pause_for_auth(app, state, &account, err.message_for_user());
+                }
+                queued_from = Some((index, err.message_for_user()));
+                break;
+            }
+            Err(err) => {
+                return Err(format!(
+                    "Pinboard rejected tag change: {}",
+                    err.message_for_user()
+                ))
+            }
+        }
+    }
+
+    Ok(match queued_from {
+        Some((index, reason)) => TagMutationResult {
+            status: "queued".to_string(),
+            message: format!(
+                "{} tag change(s) queued for retry: {reason}",
+                operations.len() - index
+            ),
+            queued: true,
+            affected,
+            affected_bookmarks,
+        },
+        None => TagMutationResult {
+            status: "sent".to_string(),
+            message: "Tags updated on Pinboard".to_string(),
+            queued: false,
+            affected,
+            affected_bookmarks,
+        },
+    })
+}
+
+fn apply_tag_mutation_locally(state: &AppState, operation: &QueueOperation) {
+    // The cache is refreshed from Pinboard later; a local miss is harmless.
+    let _ = match operation {
+        QueueOperation::RenameTag { from, to } => state.tag_cache.rename(from, to),
+        QueueOperation::DeleteTag { tag } => state.tag_cache.remove(tag),
+        QueueOperation::AddBookmark { .. } | QueueOperation::DeleteBookmark { .. } => Ok(()),
+    };
+}
+
+fn record_tag_usage(state: &AppState, account: &str, payload: &BookmarkPayload) {
+    // Tag history, tag cache and the local collection are conveniences; never
+    // fail a submit over them. The next sync replaces the local entry. A merge
+    // that is still queued is left out: only Pinboard knows its result yet.
+    if !matches!(payload.intent, SubmitIntent::Merge) {
+        let known = state.library.get(&payload.url).ok().flatten();
+        let time = payload
+            .created_at
+            .clone()
+            .or_else(|| known.map(|bookmark| bookmark.time))
+            .unwrap_or_else(|| {
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            });
+        let _ = state.library.upsert(&ExistingBookmark {
+            url: payload.url.clone(),
+            title: payload.title.clone(),
+            notes: payload.notes.clone(),
+            tags: tag_strings(&payload.tags),
+            private: payload.private,
+            read_later: payload.read_later,
+            time,
+            meta: String::new(),
+        });
+    }
+    let tags = tag_strings(&payload.tags);
+    let _ = state.tag_cache.record_usage(&tags);
+    if let Some(host) = url_host(&payload.url) {
+        let _ = state.tag_history.record(account, &host, &tags);
+    }
+}
+
 fn extract_html_title(html: &str) -> Option<String> {
     let start_title = find_ascii_case_insensitive(html, "<title")?;
     let start_content = html[start_title..].find('>')? + start_title + 1;
@@ -342,3 +1750,431 @@ fn parse_omarchy_colors(raw: &str) -> BTreeMap<String, String> {
 
     colors
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use tauri::Manager;
+
+    use super::{
+        check_duplicate, create_backup, delete_bookmark, delete_user_tag, mark_bookmark_read,
+        queue_conflicts, queue_retry_now, restore_backup, submit_bookmark, undo_last_submission,
+    };
+    use crate::api::mock_server::{app_state, MockPinboard, MockReply, TOKEN};
+    use crate::backup::archive::RestoreMode;
+    use crate::domain::accounts::AccountSettings;
+    use crate::domain::bookmark::{BookmarkPayload, ExistingBookmark, SubmitIntent};
+    use crate::domain::tags::Tag;
+    use crate::domain::validation::{BookmarkField, MAX_NOTES_CHARS};
+    use crate::infra::db::open_db;
+    use crate::queue::store::QueueOperation;
+    use crate::security::sealed::{seal_with, Kdf};
+    use crate::settings::store::ACCOUNTS_KEY;
+
+    fn bookmark() -> BookmarkPayload {
+        BookmarkPayload {
+            url: "https://example.com/article".to_string(),
+            title: "An article".to_string(),
+            notes: String::new(),
+            tags: vec![Tag::parse("reading").unwrap()],
+            private: false,
+            read_later: true,
+            intent: SubmitIntent::Create,
+            created_at: None,
+            base: None,
+        }
+    }
+
+    fn make_queue_due(path: &PathBuf) {
+        open_db(path)
+            .unwrap()
+            .execute("UPDATE queue_items SET next_attempt_at = 0", [])
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_submit_is_queued_and_sent_on_retry() {
+        let server = MockPinboard::start().await;
+        let (state, db) = app_state(&server, "submit");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        server.reply(MockReply::Status(502));
+        let result = submit_bookmark(app.handle().clone(), app.state(), bookmark())
+            .await
+            .unwrap();
+        assert_eq!(result.status, "queued");
+
+        // Not due yet, so a manual retry leaves it alone.
+        let retry = queue_retry_now(app.handle().clone(), app.state())
+            .await
+            .unwrap();
+        assert_eq!((retry.sent, retry.remaining), (0, 1));
+
+        make_queue_due(db.path());
+        server.reply(MockReply::Delay(Duration::from_secs(2)));
+        let retry = queue_retry_now(app.handle().clone(), app.state())
+            .await
+            .unwrap();
+        assert_eq!((retry.sent, retry.remaining), (0, 1));
+        assert_eq!(state.queue_store.list(10).unwrap()[0].attempt_count, 1);
+
+        make_queue_due(db.path());
+        let retry = queue_retry_now(app.handle().clone(), app.state())
+            .await
+            .unwrap();
+        assert_eq!((retry.sent, retry.remaining), (1, 0));
+
+        let adds = server.requests_to("posts/add");
+        assert_eq!(adds.len(), 3);
+        assert_eq!(adds[2].params["url"], "https://example.com/article");
+        assert_eq!(adds[2].params["toread"], "yes");
+    }
+
+    #[tokio::test]
+    async fn queued_conflicts_are_checked_with_each_items_account() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "conflicts");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+        state.token_store.set_token("work", "work:OTHER").unwrap();
+
+        let base = ExistingBookmark {
+            url: "https://example.com/article".to_string(),
+            title: "Before".to_string(),
+            notes: String::new(),
+            tags: Vec::new(),
+            private: false,
+            read_later: false,
+            time: "2024-01-01T00:00:00Z".to_string(),
+            meta: "abc".to_string(),
+        };
+        let payload = BookmarkPayload {
+            base: Some(Box::new(base)),
+            ..bookmark()
+        };
+        for account in ["default", "work", "personal"] {
+            let operation = QueueOperation::AddBookmark {
+                payload: payload.clone(),
+            };
+            state
+                .queue_store
+                .enqueue(account, &operation, "changed", 0)
+                .unwrap();
+        }
+        for item in state.queue_store.list(10).unwrap() {
+            state.queue_store.mark_conflict(item.id, "changed").unwrap();
+        }
+
+        // `personal` has no token, which only affects its own item.
+        let conflicts = queue_conflicts(app.state()).await.unwrap();
+        assert_eq!(conflicts.len(), 3);
+        let errors = conflicts
+            .iter()
+            .filter_map(|conflict| conflict.error.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(errors, vec!["Pinboard token is not set"]);
+        let mut tokens = server
+            .requests_to("posts/get")
+            .into_iter()
+            .map(|request| request.params["auth_token"].clone())
+            .collect::<Vec<_>>();
+        tokens.sort();
+        assert_eq!(tokens, vec![TOKEN.to_string(), "work:OTHER".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn restoring_a_sealed_token_needs_the_passphrase() {
+        let server = MockPinboard::start().await;
+        let (state, db) = app_state(&server, "restore");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        let token = seal_with(
+            "backup pass",
+            "me:RESTORED",
+            Kdf::Pbkdf2 { iterations: 1_000 },
+        );
+        let archive = serde_json::json!({
+            "format": "ommapin-backup",
+            "schemaVersion": 1,
+            "appVersion": "0.0.0",
+            "createdAt": 0,
+            "token": token.unwrap(),
+        });
+        let file = db.path().with_extension("backup.json");
+        std::fs::write(&file, archive.to_string()).unwrap();
+        let file_name = file.to_str().unwrap().to_string();
+
+        let missing =
+            restore_backup(app.state(), file_name.clone(), RestoreMode::Merge, None).await;
+        assert!(missing.unwrap_err().contains("passphrase"));
+        assert_eq!(
+            state.token_store.get_token("default").unwrap().as_deref(),
+            Some(TOKEN)
+        );
+
+        let summary = restore_backup(
+            app.state(),
+            file_name,
+            RestoreMode::Merge,
+            Some("backup pass".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.tokens, vec!["default"]);
+        assert_eq!(
+            state.token_store.get_token("default").unwrap().as_deref(),
+            Some("me:RESTORED")
+        );
+
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[tokio::test]
+    async fn backups_carry_every_accounts_token() {
+        let server = MockPinboard::start().await;
+        let (source, db) = app_state(&server, "backup-accounts");
+        let app = tauri::test::mock_app();
+        app.manage(source.clone());
+        let mut accounts = AccountSettings::default();
+        accounts.upsert("work", "work");
+        source.settings_store.set(ACCOUNTS_KEY, &accounts).unwrap();
+        source.token_store.set_token("work", "work:OTHER").unwrap();
+
+        let file = db.path().with_extension("backup.json");
+        let file_name = file.to_str().unwrap().to_string();
+        let created = create_backup(app.state(), file_name.clone(), Some("pass".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(created.tokens, vec!["default", "work"]);
+
+        let (target, _target_db) = app_state(&server, "backup-accounts-target");
+        target.token_store.clear_token("default").unwrap();
+        let app = tauri::test::mock_app();
+        app.manage(target.clone());
+        let restored = restore_backup(
+            app.state(),
+            file_name,
+            RestoreMode::Merge,
+            Some("pass".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(restored.tokens, vec!["default", "work"]);
+        assert_eq!(
+            target.token_store.get_token("default").unwrap().as_deref(),
+            Some(TOKEN)
+        );
+        assert_eq!(
+            target.token_store.get_token("work").unwrap().as_deref(),
+            Some("work:OTHER")
+        );
+
+        let _ = std::fs::remove_file(&file);
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens_queue_deletes_and_pause_the_account() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "unauthorized");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        server.reply(MockReply::Status(401));
+        let deleted = delete_bookmark(
+            app.handle().clone(),
+            app.state(),
+            " example.com/article ".to_string(),
+            true,
+        )
+        .await
+        .unwrap();
+        assert!(deleted.queued);
+        assert_eq!(
+            server.requests_to("posts/delete")[0].params["url"],
+            "https://example.com/article"
+        );
+        assert_eq!(state.queue_store.paused_accounts(), vec!["default"]);
+
+        server.reply(MockReply::Status(403));
+        let tags = delete_user_tag(app.handle().clone(), app.state(), "old".to_string(), false)
+            .await
+            .unwrap();
+        assert!(tags.queued);
+        assert_eq!(state.queue_store.stats().unwrap().pending, 2);
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens_queue_undo_and_pause_the_account() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "undo-unauthorized");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        check_duplicate(app.state(), "https://example.com/article".to_string())
+            .await
+            .unwrap();
+        submit_bookmark(app.handle().clone(), app.state(), bookmark())
+            .await
+            .unwrap();
+
+        server.reply(MockReply::Status(401));
+        let undone = undo_last_submission(app.handle().clone(), app.state())
+            .await
+            .unwrap();
+        assert!(undone.queued);
+        assert_eq!(state.queue_store.paused_accounts(), vec!["default"]);
+        assert_eq!(state.queue_store.stats().unwrap().pending, 1);
+    }
+
+    #[tokio::test]
+    async fn merged_submits_are_stored_as_written() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "merge");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        server.reply(MockReply::Json(serde_json::json!({
+            "posts": [{
+                "href": "https://example.com/article",
+                "description": "Original title",
+                "extended": "old notes",
+                "tags": "archive",
+                "shared": "yes",
+                "toread": "no",
+                "time": "2020-01-02T03:04:05Z",
+            }],
+        })));
+        let merge = BookmarkPayload {
+            notes: "new notes".to_string(),
+            intent: SubmitIntent::Merge,
+            ..bookmark()
+        };
+        let result = submit_bookmark(app.handle().clone(), app.state(), merge)
+            .await
+            .unwrap();
+        assert_eq!(result.status, "sent");
+
+        let stored = state
+            .library
+            .get("https://example.com/article")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.title, "Original title");
+        assert_eq!(stored.tags, vec!["archive", "reading"]);
+        assert!(stored.notes.starts_with("old notes") && stored.notes.ends_with("new notes"));
+        assert_eq!(stored.time, "2020-01-02T03:04:05Z");
+    }
+
+    #[tokio::test]
+    async fn marking_read_rewrites_pinboards_copy_not_the_local_one() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "mark-read");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+
+        let stale = ExistingBookmark {
+            url: "https://example.com/article".to_string(),
+            title: "Stale title".to_string(),
+            notes: String::new(),
+            tags: Vec::new(),
+            private: false,
+            read_later: true,
+            time: "2020-01-02T03:04:05Z".to_string(),
+            meta: "old".to_string(),
+        };
+        state.library.upsert(&stale).unwrap();
+        let remote = serde_json::json!({
+            "posts": [{
+                "href": "https://example.com/article",
+                "description": "Edited elsewhere",
+                "extended": "",
+                "tags": "later",
+                "shared": "yes",
+                "toread": "yes",
+                "time": "2020-01-02T03:04:05Z",
+                "meta": "new",
+            }],
+        });
+        server.reply(MockReply::Json(remote.clone()));
+        server.reply(MockReply::Json(remote));
+
+        let result = mark_bookmark_read(
+            app.handle().clone(),
+            app.state(),
+            " example.com/article ".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.status, "sent");
+
+        let adds = server.requests_to("posts/add");
+        assert_eq!(adds.len(), 1);
+        assert_eq!(adds[0].params["description"], "Edited elsewhere");
+        assert_eq!(adds[0].params["tags"], "later");
+        assert_eq!(adds[0].params["toread"], "no");
+        assert_eq!(server.requests_to("posts/get").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn undo_replays_on_the_account_the_save_went_to() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "undo-account");
+        let app = tauri::test::mock_app();
+        app.manage(state.clone());
+        state.token_store.set_token("work", "work:OTHER").unwrap();
+
+        check_duplicate(app.state(), "https://example.com/article".to_string())
+            .await
+            .unwrap();
+        let result = submit_bookmark(app.handle().clone(), app.state(), bookmark())
+            .await
+            .unwrap();
+        assert_eq!(result.status, "sent");
+
+        let mut accounts = AccountSettings::default();
+        accounts.upsert("work", "work");
+        accounts.select("work").unwrap();
+        state.settings_store.set(ACCOUNTS_KEY, &accounts).unwrap();
+
+        let undone = undo_last_submission(app.handle().clone(), app.state())
+            .await
+            .unwrap();
+        assert_eq!(undone.status, "sent");
+        let deletes = server.requests_to("posts/delete");
+        assert_eq!(deletes.len(), 1);
+        assert_eq!(deletes[0].params["auth_token"], TOKEN);
+    }
+
+    #[tokio::test]
+    async fn merges_over_the_notes_limit_are_rejected_before_sending() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "merge-limit");
+        let app = tauri::test::mock_app();
+        app.manage(state);
+
+        server.reply(MockReply::Json(serde_json::json!({
+            "posts": [{
+                "href": "https://example.com/article",
+                "description": "Original title",
+                "extended": "x".repeat(MAX_NOTES_CHARS - 10),
+                "tags": "",
+                "time": "2020-01-02T03:04:05Z",
+            }],
+        })));
+        let merge = BookmarkPayload {
+            notes: "twenty more characters".to_string(),
+            intent: SubmitIntent::Merge,
+            ..bookmark()
+        };
+        let result = submit_bookmark(app.handle().clone(), app.state(), merge)
+            .await
+            .unwrap();
+        assert_eq!(result.status, "invalid");
+        assert_eq!(result.field_errors[0].field, BookmarkField::Notes);
+        assert!(server.requests_to("posts/add").is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::accounts::{
+    validate_account_name, validate_account_settings, AccountSettings, DEFAULT_ACCOUNT,
+};
+use crate::domain::bookmark::UserTag;
+use crate::domain::bundles::{validate_bundles, TagBundle};
+use crate::domain::rules::{validate_rules, DomainRule};
+use crate::domain::tags::TagNormalization;
+use crate::queue::store::QueueOperation;
+use crate::security::credentials::CredentialSettings;
+use crate::security::sealed::SealedSecret;
+use crate::settings::store::{
+    ACCOUNTS_KEY, CREDENTIALS_KEY, DOMAIN_RULES_KEY, TAG_BUNDLES_KEY, TAG_NORMALIZATION_KEY,
+};
+use crate::tagging::history::HistoryEntry;
+
+pub const BACKUP_FORMAT: &str = "ommapin-backup";
+/// Bumped whenever the archive layout changes incompatibly.
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("not an ommapin backup")]
+    NotABackup,
+    #[error("backup schema {0} is newer than this version of ommapin supports ({BACKUP_SCHEMA_VERSION})")]
+    TooNew(u32),
+    #[error("backup queue item {index} is invalid: {message}")]
+    InvalidQueueItem { index: usize, message: String },
+    #[error("backup setting `{key}` is invalid: {message}")]
+    InvalidSetting { key: String, message: String },
+    #[error("backup setting `{0}` is not known to this version of ommapin")]
+    UnknownSetting(String),
+    #[error("this backup includes a sealed token; enter the passphrase it was created with")]
+    PassphraseRequired,
+    #[error("backup token for `{0}` does not name a valid account")]
+    InvalidTokenAccount(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestoreMode {
+    /// Adds what is missing locally; existing settings win.
+    Merge,
+    /// Discards local queue, settings and tag data first.
+    Replace,
+}
+
+/// A queue row as stored, with the operation kept as raw JSON so rows from
+/// any version survive unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueRecord {
+    pub payload: Value,
+    #[serde(default = "default_account")]
+    pub account: String,
+    pub status: String,
+    pub attempt_count: i64,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
+fn default_account() -> String {
+    DEFAULT_ACCOUNT.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupArchive {
+    pub format: String,
+    pub schema_version: u32,
+    pub app_version: String,
+    pub created_at: i64,
+    #[serde(default)]
+    pub queue: Vec<QueueRecord>,
+    /// Every settings row: domain rules, tag normalization, tag bundles, ...
+    #[serde(default)]
+    pub settings: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub tag_history: Vec<HistoryEntry>,
+    #[serde(default)]
+    pub user_tags: Vec<UserTag>,
+    /// A single token from archives written before accounts had their own;
+    /// it is restored to whichever account is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<SealedSecret>,
+    /// Each account's Pinboard token, sealed with a passphrase chosen at
+    /// backup time.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tokens: BTreeMap<String, SealedSecret>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSummary {
+    pub queue_items: usize,
+    pub settings: usize,
+    pub tag_history: usize,
+    pub user_tags: usize,
+    /// Accounts whose token the archive carries, or that a restore set.
+    pub tokens: Vec<String>,
+}
+
+impl BackupArchive {
+    pub fn summary(&self) -> BackupSummary {
+        BackupSummary {
+            queue_items: self.queue.len(),
+            settings: self.settings.len(),
+            tag_history: self.tag_history.len(),
+            user_tags: self.user_tags.len(),
+            tokens: self.tokens.keys().cloned().collect(),
+        }
+    }
+}
+
+/// Checks that an archive can be restored without leaving bad state behind:
+/// right format, a schema this build understands and settings that would
+/// pass the same validation as when they were saved.
+pub fn validate_archive(archive: &BackupArchive) -> Result<(), BackupError> {
+    if archive.format != BACKUP_FORMAT || archive.schema_version == 0 {
+        return Err(BackupError::NotABackup);
+    }
+    if archive.schema_version > BACKUP_SCHEMA_VERSION {
+        return Err(BackupError::TooNew(archive.schema_version));
+    }
+
+    for (index, record) in archive.queue.iter().enumerate() {
+        serde_json::from_value::<QueueOperation>(record.payload.clone()).map_err(|e| {
+            BackupError::InvalidQueueItem {
+                index,
+                message: e.to_string(),
+            }
+        })?;
+    }
+
+    for account in archive.tokens.keys() {
+        if validate_account_name(account).ok().as_deref() != Some(account.as_str()) {
+            return Err(BackupError::InvalidTokenAccount(account.clone()));
+        }
+    }
+
+    for (key, value) in &archive.settings {
+        let invalid = |message: String| BackupError::InvalidSetting {
+            key: key.clone(),
+            message,
+        };
+        match key.as_str() {
+            DOMAIN_RULES_KEY => {
+                let rules = serde_json::from_value::<Vec<DomainRule>>(value.clone())
+                    .map_err(|e| invalid(e.to_string()))?;
+                validate_rules(&rules).map_err(|e| invalid(e.to_string()))?;
+            }
+            TAG_NORMALIZATION_KEY => {
+                serde_json::from_value::<TagNormalization>(value.clone())
+                    .map_err(|e| invalid(e.to_string()))?
+                    .validate()
+                    .map_err(|e| invalid(e.to_string()))?;
+            }
+            TAG_BUNDLES_KEY => {
+                let bundles = serde_json::from_value::<Vec<TagBundle>>(value.clone())
+                    .map_err(|e| invalid(e.to_string()))?;
+                validate_bundles(&bundles).map_err(|e| invalid(e.to_string()))?;
+            }
+            CREDENTIALS_KEY => {
+                serde_json::from_value::<CredentialSettings>(value.clone())
+                    .map_err(|e| invalid(e.to_string()))?
+                    .validated()
+                    .map_err(|e| invalid(e.to_string()))?;
+            }
+            ACCOUNTS_KEY => {
+                let accounts = serde_json::from_value::<AccountSettings>(value.clone())
+                    .map_err(|e| invalid(e.to_string()))?;
+                validate_account_settings(&accounts).map_err(|e| invalid(e.to_string()))?;
+            }
+            _ => return Err(BackupError::UnknownSetting(key.clone())),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{validate_archive, BackupArchive, BackupError, BACKUP_SCHEMA_VERSION};
+    use crate::security::sealed::{seal_with, Kdf};
+
+    fn archive(schema_version: u32) -> BackupArchive {
+        serde_json::from_value(json!({
+            "format": "ommapin-backup",
+            "schemaVersion": schema_version,
+            "appVersion": "0.0.0",
+            "createdAt": 0,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn rejects_foreign_newer_and_invalid_archives() {
+        assert!(validate_archive(&archive(BACKUP_SCHEMA_VERSION)).is_ok());
+        assert!(matches!(
+            validate_archive(&archive(BACKUP_SCHEMA_VERSION + 1)),
+            Err(BackupError::TooNew(_))
+        ));
+
+        let mut foreign = archive(BACKUP_SCHEMA_VERSION);
+        foreign.format = "something-else".to_string();
+        assert!(matches!(
+            validate_archive(&foreign),
+            Err(BackupError::NotABackup)
+        ));
+
+        let mut bad_bundles = archive(BACKUP_SCHEMA_VERSION);
+        bad_bundles.settings.insert(
+            "tag_bundles".to_string(),
+            json!([{ "name": "", "tags": ["a"] }]),
+        );
+        assert!(matches!(
+            validate_archive(&bad_bundles),
+            Err(BackupError::InvalidSetting { .. })
+        ));
+
+        let mut bad_pass_entry = archive(BACKUP_SCHEMA_VERSION);
+        bad_pass_entry.settings.insert(
+            "credential_storage".to_string(),
+            json!({ "passEntry": "--help" }),
+        );
+        assert!(matches!(
+            validate_archive(&bad_pass_entry),
+            Err(BackupError::InvalidSetting { .. })
+        ));
+
+        let mut bad_backend = archive(BACKUP_SCHEMA_VERSION);
+        bad_backend.settings.insert(
+            "accounts".to_string(),
+            json!({
+                "accounts": [{ "name": "work", "backend": { "baseUrl": "ftp://example.com" } }],
+                "active": "work",
+            }),
+        );
+        assert!(matches!(
+            validate_archive(&bad_backend),
+            Err(BackupError::InvalidSetting { .. })
+        ));
+
+        let mut bad_token_account = archive(BACKUP_SCHEMA_VERSION);
+        bad_token_account.tokens.insert(
+            "Work Laptop".to_string(),
+            seal_with("pass", "me:SECRET", Kdf::Pbkdf2 { iterations: 1_000 }).unwrap(),
+        );
+        assert!(matches!(
+            validate_archive(&bad_token_account),
+            Err(BackupError::InvalidTokenAccount(account)) if account == "Work Laptop"
+        ));
+
+        let mut unknown = archive(BACKUP_SCHEMA_VERSION);
+        unknown
+            .settings
+            .insert("something_new".to_string(), json!(true));
+        assert!(matches!(
+            validate_archive(&unknown),
+            Err(BackupError::UnknownSetting(key)) if key == "something_new"
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use rusqlite::{params, Transaction};
+
+use crate::backup::archive::{
+    BackupArchive, BackupSummary, QueueRecord, RestoreMode, BACKUP_FORMAT, BACKUP_SCHEMA_VERSION,
+};
+use crate::domain::bookmark::UserTag;
+use crate::infra::db::{database_path, now_unix, open_db};
+use crate::queue::store::QueueOperation;
+use crate::tagging::history::HistoryEntry;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupStoreError {
+    #[error("db error: {0}")]
+    Db(String),
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+/// Reads and writes the tables that make up a backup archive. Tokens are
+/// handled by the caller, since they live in the keyring rather than here.
+pub struct BackupStore {
+    db_path: PathBuf,
+}
+
+impl BackupStore {
+    pub fn new(custom_path: &str) -> Result<Self, BackupStoreError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| BackupStoreError::Db(e.to_string()))?;
+        Ok(Self { db_path })
+    }
+
+    pub fn snapshot(&self) -> Result<BackupArchive, BackupStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| BackupStoreError::Db(e.to_string()))?;
+        let db = |e: rusqlite::Error| BackupStoreError::Db(e.to_string());
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT payload_json, status, attempt_count, next_attempt_at, last_error, created_at,
+                        account
+                 FROM queue_items ORDER BY id ASC",
+            )
+            .map_err(db)?;
+        let queue = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    QueueRecord {
+                        payload: serde_json::Value::Null,
+                        account: row.get(6)?,
+                        status: row.get(1)?,
+                        attempt_count: row.get(2)?,
+                        next_attempt_at: row.get(3)?,
+                        last_error: row.get(4)?,
+                        created_at: row.get(5)?,
+                    },
+                ))
+            })
+            .map_err(db)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db)?
+            .into_iter()
+            .map(|(payload_json, record)| {
+                serde_json::from_str(&payload_json)
+                    .map(|payload| QueueRecord { payload, ..record })
+                    .map_err(|e| BackupStoreError::Serde(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn
+            .prepare("SELECT key, value_json FROM settings ORDER BY key ASC")
+            .map_err(db)?;
+        let settings = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db)?
+            .into_iter()
+            .map(|(key, value_json)| {
+                serde_json::from_str(&value_json)
+                    .map(|value| (key, value))
+                    .map_err(|e| BackupStoreError::Serde(e.to_string()))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        let mut stmt = conn
+            .prepare("SELECT account, host, tags_json, created_at FROM tag_history ORDER BY id ASC")
+            .map_err(db)?;
+        let tag_history = stmt
+            .query_map([], |row| {
+                let tags_json: String = row.get(2)?;
+                Ok(HistoryEntry {
+                    account: row.get(0)?,
+                    host: row.get(1)?,
+                    tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(db)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db)?;
+
+        let mut stmt = conn
+            .prepare("SELECT tag, count FROM user_tags ORDER BY count DESC, tag ASC")
+            .map_err(db)?;
+        let user_tags = stmt
+            .query_map([], |row| {
+                Ok(UserTag {
+                    tag: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })
+            .map_err(db)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db)?;
+
+        Ok(BackupArchive {
+            format: BACKUP_FORMAT.to_string(),
+            schema_version: BACKUP_SCHEMA_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now_unix(),
+            queue,
+            settings,
+            tag_history,
+            user_tags,
+            token: None,
+            tokens: BTreeMap::new(),
+        })
+    }
+
+    /// Writes an already validated archive in one transaction. `Merge` keeps
+    /// local settings and skips queue items and history entries that are
+    /// already present; `Replace` clears the tables first.
+    pub fn restore(
+        &self,
+        archive: &BackupArchive,
+        mode: RestoreMode,
+    ) -> Result<BackupSummary, BackupStoreError> {
+        let mut conn = open_db(&self.db_path).map_err(|e| BackupStoreError::Db(e.to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+
+        if mode == RestoreMode::Replace {
+            tx.execute_batch(
+                "DELETE FROM queue_items;
+                 DELETE FROM settings;
+                 DELETE FROM tag_history;
+                 DELETE FROM user_tags;",
+            )
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+        }
+
+        let summary = BackupSummary {
+            queue_items: restore_queue(&tx, &archive.queue)?,
+            settings: restore_settings(&tx, &archive.settings)?,
+            tag_history: restore_tag_history(&tx, &archive.tag_history)?,
+            user_tags: restore_user_tags(&tx, &archive.user_tags)?,
+            tokens: Vec::new(),
+        };
+
+        tx.commit()
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+        Ok(summary)
+    }
+}
+
+fn restore_queue(tx: &Transaction<'_>, queue: &[QueueRecord]) -> Result<usize, BackupStoreError> {
+    let now = now_unix();
+    let mut restored = 0;
+    for record in queue {
+        // Re-serialized through the operation type so the text matches what
+        // the queue itself writes and duplicates are recognised.
+        let operation = serde_json::from_value::<QueueOperation>(record.payload.clone())
+            .map_err(|e| BackupStoreError::Serde(e.to_string()))?;
+        let payload_json = serde_json::to_string(&operation)
+            .map_err(|e| BackupStoreError::Serde(e.to_string()))?;
+        restored += tx
+            .execute(
+                "INSERT INTO queue_items(payload_json, account, status, attempt_count, next_attempt_at, last_error, created_at, updated_at)
+                 SELECT ?1, ?8, ?2, ?3, ?4, ?5, ?6, ?7
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM queue_items WHERE payload_json = ?1 AND account = ?8
+                 )",
+                params![
+                    payload_json,
+                    record.status,
+                    record.attempt_count,
+                    record.next_attempt_at,
+                    record.last_error,
+                    record.created_at,
+                    now,
+                    record.account
+                ],
+            )
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+    }
+    Ok(restored)
+}
+
+fn restore_settings(
+    tx: &Transaction<'_>,
+    settings: &BTreeMap<String, serde_json::Value>,
+) -> Result<usize, BackupStoreError> {
+    let now = now_unix();
+    let mut restored = 0;
+    for (key, value) in settings {
+        let value_json =
+            serde_json::to_string(value).map_err(|e| BackupStoreError::Serde(e.to_string()))?;
+        restored += tx
+            .execute(
+                "INSERT INTO settings(key, value_json, updated_at) VALUES(?1, ?2, ?3)
+                 ON CONFLICT(key) DO NOTHING",
+                params![key, value_json, now],
+            )
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+    }
+    Ok(restored)
+}
+
+fn restore_tag_history(
+    tx: &Transaction<'_>,
+    entries: &[HistoryEntry],
+) -> Result<usize, BackupStoreError> {
+    let mut restored = 0;
+    for entry in entries {
+        let tags_json = serde_json::to_string(&entry.tags)
+            .map_err(|e| BackupStoreError::Serde(e.to_string()))?;
+        restored += tx
+            .execute(
+                "INSERT INTO tag_history(host, tags_json, created_at, account)
+                 SELECT ?1, ?2, ?3, ?4
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM tag_history
+                     WHERE host = ?1 AND tags_json = ?2 AND created_at = ?3 AND account = ?4
+                 )",
+                params![entry.host, tags_json, entry.created_at, entry.account],
+            )
+            .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+    }
+    Ok(restored)
+}
+
+fn restore_user_tags(tx: &Transaction<'_>, tags: &[UserTag]) -> Result<usize, BackupStoreError> {
+    let now = now_unix();
+    for tag in tags {
+        tx.execute(
+            "INSERT INTO user_tags(tag, count, updated_at) VALUES(?1, ?2, ?3)
+             ON CONFLICT(tag) DO UPDATE SET count = MAX(count, excluded.count)",
+            params![tag.tag, tag.count, now],
+        )
+        .map_err(|e| BackupStoreError::Db(e.to_string()))?;
+    }
+    Ok(tags.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::BackupStore;
+    use crate::backup::archive::RestoreMode;
+    use crate::domain::bookmark::UserTag;
+    use crate::infra::db::TempDb;
+    use crate::tagging::history::HistoryEntry;
+
+    #[test]
+    fn merge_skips_existing_rows_and_replace_starts_over() {
+        let db = TempDb::new("backup");
+        let store = BackupStore::new(db.path().to_str().unwrap()).unwrap();
+
+        let mut archive = store.snapshot().unwrap();
+        archive.queue.push(
+            serde_json::from_value(json!({
+                "payload": { "kind": "deleteBookmark", "url": "https://a.example/" },
+                "status": "pending",
+                "attemptCount": 2,
+                "nextAttemptAt": 10,
+                "lastError": "timeout",
+                "createdAt": 5,
+            }))
+            .unwrap(),
+        );
+        archive.settings.insert(
+            "tag_bundles".to_string(),
+            json!([{ "name": "x", "tags": ["a"] }]),
+        );
+        archive.tag_history.push(HistoryEntry {
+            account: "work".to_string(),
+            host: "a.example".to_string(),
+            tags: vec!["a".to_string()],
+            created_at: 7,
+        });
+        archive.user_tags.push(UserTag {
+            tag: "a".to_string(),
+            count: 3,
+        });
+
+        let first = store.restore(&archive, RestoreMode::Merge).unwrap();
+        assert_eq!(
+            (first.queue_items, first.settings, first.tag_history),
+            (1, 1, 1)
+        );
+        let again = store.restore(&archive, RestoreMode::Merge).unwrap();
+        assert_eq!(
+            (again.queue_items, again.settings, again.tag_history),
+            (0, 0, 0)
+        );
+
+        let snapshot = store.snapshot().unwrap();
+        assert_eq!(snapshot.queue.len(), 1);
+        assert_eq!(snapshot.queue[0].attempt_count, 2);
+        assert_eq!(snapshot.user_tags[0].count, 3);
+        assert_eq!(snapshot.tag_history[0].account, "work");
+
+        let mut smaller = archive.clone();
+        smaller.queue.clear();
+        store.restore(&smaller, RestoreMode::Replace).unwrap();
+        let snapshot = store.snapshot().unwrap();
+        assert!(snapshot.queue.is_empty());
+        assert_eq!(snapshot.settings.len(), 1);
+        assert_eq!(snapshot.tag_history.len(), 1);
+    }
+}
@@ -1,24 +1,26 @@
 use std::sync::Arc;
 
 use crate::domain::bookmark::{normalize_url, DuplicateCheckResult};
+use crate::security::accounts::{active_credentials, Credentials};
 use crate::AppState;
 
 pub async fn check_duplicate_for_url(
     state: &Arc<AppState>,
     raw_url: &str,
 ) -> Result<DuplicateCheckResult, String> {
-    let token = state
-        .token_store
-        .get_token()
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Pinboard token is not set".to_string())?;
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(state)?;
 
     let normalized = normalize_url(raw_url).ok_or_else(|| "Invalid URL".to_string())?;
-    let existing = state
-        .pinboard
+    let existing = client
         .get_existing_bookmark(&token, &normalized)
         .await
         .map_err(|e| e.to_string())?;
+    // Snapshot for undo; a failure here only means the next save can't be undone.
+    let _ = state.undo.capture(&account, &normalized, existing.as_ref());
 
     Ok(DuplicateCheckResult {
         exists: existing.is_some(),
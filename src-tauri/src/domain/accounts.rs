@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+
+/// The account that tokens saved before accounts existed belong to.
+pub const DEFAULT_ACCOUNT: &str = "default";
+const MAX_ACCOUNT_NAME_CHARS: usize = 32;
+const MAX_WRITE_INTERVAL_SECS: u64 = 3600;
+/// Pinboard's own pacing, kept for backends saved without an interval.
+const DEFAULT_WRITE_INTERVAL_SECS: u64 = 3;
+
+/// A named Pinboard account; its token is kept in the keyring under the name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub name: String,
+    /// Pinboard username, as confirmed when the token was saved.
+    #[serde(default)]
+    pub username: String,
+    /// A Pinboard-compatible server used instead of `api.pinboard.in`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<AccountBackend>,
+}
+
+/// How the token is sent to the API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthStyle {
+    /// `auth_token=username:TOKEN` in the query, as Pinboard expects.
+    #[default]
+    QueryToken,
+    /// `Authorization: Bearer <token>`.
+    Bearer,
+    /// HTTP basic auth; the token is stored as `username:password`.
+    Basic,
+}
+
+/// A self-hosted server speaking the Pinboard v1 API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBackend {
+    /// API root that endpoints like `posts/add` are appended to.
+    pub base_url: String,
+    #[serde(default)]
+    pub auth_style: AuthStyle,
+    /// Minimum seconds between writes; 0 disables pacing.
+    #[serde(default = "default_write_interval")]
+    pub write_interval_secs: u64,
+}
+
+fn default_write_interval() -> u64 {
+    DEFAULT_WRITE_INTERVAL_SECS
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSettings {
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default = "default_account_name")]
+    pub active: String,
+}
+
+impl Default for AccountSettings {
+    fn default() -> Self {
+        Self {
+            accounts: Vec::new(),
+            active: default_account_name(),
+        }
+    }
+}
+
+fn default_account_name() -> String {
+    DEFAULT_ACCOUNT.to_string()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AccountError {
+    #[error("account names cannot be empty")]
+    EmptyName,
+    #[error("account name `{0}` may only use lowercase letters, digits, `-` and `_` (up to 32)")]
+    InvalidName(String),
+    #[error("account `{0}` does not exist")]
+    Unknown(String),
+    #[error("API base URL `{0}` must be an http or https URL")]
+    InvalidBaseUrl(String),
+    #[error("API base URL `{0}` must use https unless the server runs on this machine")]
+    InsecureBaseUrl(String),
+    #[error("the write interval cannot be longer than {MAX_WRITE_INTERVAL_SECS} seconds")]
+    IntervalTooLong,
+}
+
+impl AccountSettings {
+    /// The default account is always available, even before a token was saved for it.
+    pub fn contains(&self, name: &str) -> bool {
+        name == DEFAULT_ACCOUNT || self.accounts.iter().any(|account| account.name == name)
+    }
+
+    /// Every account name, the default account first.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![DEFAULT_ACCOUNT.to_string()];
+        for account in &self.accounts {
+            if account.name != DEFAULT_ACCOUNT {
+                names.push(account.name.clone());
+            }
+        }
+        names
+    }
+
+    /// Adds the account or updates its username.
+    pub fn upsert(&mut self, name: &str, username: &str) {
+        match self
+            .accounts
+            .iter_mut()
+            .find(|account| account.name == name)
+        {
+            Some(account) => account.username = username.to_string(),
+            None => self.accounts.push(Account {
+                name: name.to_string(),
+                username: username.to_string(),
+                backend: None,
+            }),
+        }
+    }
+
+    pub fn backend(&self, name: &str) -> Option<&AccountBackend> {
+        self.accounts
+            .iter()
+            .find(|account| account.name == name)
+            .and_then(|account| account.backend.as_ref())
+    }
+
+    /// Points the account at `backend`, or back at Pinboard with `None`. The
+    /// account is added if needed, so a backend can be chosen before the
+    /// token is saved.
+    pub fn set_backend(&mut self, name: &str, backend: Option<AccountBackend>) {
+        if !self.accounts.iter().any(|account| account.name == name) {
+            self.upsert(name, "");
+        }
+        if let Some(account) = self
+            .accounts
+            .iter_mut()
+            .find(|account| account.name == name)
+        {
+            account.backend = backend;
+        }
+    }
+
+    /// Drops the account; if it was active, the default account takes over.
+    pub fn remove(&mut self, name: &str) {
+        self.accounts.retain(|account| account.name != name);
+        if self.active == name {
+            self.active = default_account_name();
+        }
+    }
+
+    pub fn select(&mut self, name: &str) -> Result<(), AccountError> {
+        if !self.contains(name) {
+            return Err(AccountError::Unknown(name.to_string()));
+        }
+        self.active = name.to_string();
+        Ok(())
+    }
+}
+
+/// Trims and checks an account name; names end up in keyring entries and
+/// queue rows, so they are kept to a small character set.
+pub fn validate_account_name(name: &str) -> Result<String, AccountError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AccountError::EmptyName);
+    }
+    let valid = name.chars().count() <= MAX_ACCOUNT_NAME_CHARS
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_');
+    if !valid {
+        return Err(AccountError::InvalidName(name.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// Checks settings that did not come through the account commands, e.g.
+/// from a backup: every name and backend must be valid and the active
+/// account must exist.
+pub fn validate_account_settings(settings: &AccountSettings) -> Result<(), AccountError> {
+    for account in &settings.accounts {
+        if validate_account_name(&account.name)? != account.name {
+            return Err(AccountError::InvalidName(account.name.clone()));
+        }
+        if let Some(backend) = &account.backend {
+            validate_backend(backend)?;
+        }
+    }
+    if !settings.contains(&settings.active) {
+        return Err(AccountError::Unknown(settings.active.clone()));
+    }
+    Ok(())
+}
+
+/// Checks a backend and trims the trailing slash off its base URL. Plain
+/// http is only allowed for loopback hosts, since every request carries the
+/// token.
+pub fn validate_backend(backend: &AccountBackend) -> Result<AccountBackend, AccountError> {
+    let base_url = backend.base_url.trim().trim_end_matches('/');
+    let url = Url::parse(base_url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+        .ok_or_else(|| AccountError::InvalidBaseUrl(base_url.to_string()))?;
+    let loopback = match url.host() {
+        Some(Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    };
+    if url.scheme() == "http" && !loopback {
+        return Err(AccountError::InsecureBaseUrl(base_url.to_string()));
+    }
+    if backend.write_interval_secs > MAX_WRITE_INTERVAL_SECS {
+        return Err(AccountError::IntervalTooLong);
+    }
+    Ok(AccountBackend {
+        base_url: base_url.to_string(),
+        ..backend.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        validate_account_name, validate_backend, AccountBackend, AccountError, AccountSettings,
+        AuthStyle, DEFAULT_ACCOUNT,
+    };
+
+    #[test]
+    fn names_are_restricted_and_removal_falls_back_to_default() {
+        assert_eq!(validate_account_name(" work ").unwrap(), "work");
+        assert!(matches!(
+            validate_account_name("Work Stuff"),
+            Err(AccountError::InvalidName(_))
+        ));
+        assert!(matches!(
+            validate_account_name(""),
+            Err(AccountError::EmptyName)
+        ));
+
+        let mut settings = AccountSettings::default();
+        assert!(settings.contains(DEFAULT_ACCOUNT));
+        assert!(settings.select("work").is_err());
+
+        settings.upsert("work", "alice");
+        settings.select("work").unwrap();
+        settings.upsert("work", "alice2");
+        assert_eq!(settings.accounts.len(), 1);
+        assert_eq!(settings.accounts[0].username, "alice2");
+
+        settings.remove("work");
+        assert_eq!(settings.active, DEFAULT_ACCOUNT);
+        assert!(!settings.contains("work"));
+    }
+
+    #[test]
+    fn backends_are_validated_and_kept_per_account() {
+        let backend = AccountBackend {
+            base_url: " https://links.example.com/api/v1/ ".to_string(),
+            auth_style: AuthStyle::Bearer,
+            write_interval_secs: 0,
+        };
+        let clean = validate_backend(&backend).unwrap();
+        assert_eq!(clean.base_url, "https://links.example.com/api/v1");
+        for base_url in ["links.example.com", "ftp://links.example.com", ""] {
+            let backend = AccountBackend {
+                base_url: base_url.to_string(),
+                ..backend.clone()
+            };
+            assert!(matches!(
+                validate_backend(&backend),
+                Err(AccountError::InvalidBaseUrl(_))
+            ));
+        }
+        let http = |base_url: &str| AccountBackend {
+            base_url: base_url.to_string(),
+            ..backend.clone()
+        };
+        assert!(matches!(
+            validate_backend(&http("http://links.example.com/api/v1")),
+            Err(AccountError::InsecureBaseUrl(_))
+        ));
+        for local in [
+            "http://localhost:9090/api",
+            "http://127.0.0.1:9090",
+            "http://[::1]/v1",
+        ] {
+            assert!(validate_backend(&http(local)).is_ok(), "{local}");
+        }
+
+        let mut settings = AccountSettings::default();
+        settings.set_backend(DEFAULT_ACCOUNT, Some(clean.clone()));
+        settings.upsert(DEFAULT_ACCOUNT, "me");
+        assert_eq!(settings.backend(DEFAULT_ACCOUNT), Some(&clean));
+        assert_eq!(settings.backend("work"), None);
+
+        settings.set_backend(DEFAULT_ACCOUNT, None);
+        assert_eq!(settings.backend(DEFAULT_ACCOUNT), None);
+        assert_eq!(settings.accounts[0].username, "me");
+
+        let saved = serde_json::from_str::<AccountBackend>(r#"{"baseUrl":"https://x.example"}"#);
+        assert_eq!(saved.unwrap().write_interval_secs, 3);
+    }
+}
@@ -1,12 +1,18 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt;
 use url::Url;
 
+use crate::domain::tags::{parse_tag_list, Tag};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum SubmitIntent {
     Create,
     Update,
+    /// Update that keeps what is already on Pinboard: tags are unioned, notes
+    /// appended and the original date preserved.
+    Merge,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,13 +21,21 @@ pub struct BookmarkPayload {
     pub url: String,
     pub title: String,
     pub notes: String,
-    pub tags: Vec<String>,
+    pub tags: Vec<Tag>,
     pub private: bool,
     pub read_later: bool,
     pub intent: SubmitIntent,
+    /// Bookmark date sent as `dt` (RFC 3339). Updates carry the existing
+    /// bookmark's time so Pinboard keeps its place in the history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// The version an update was edited from. When set, the write is refused
+    /// if Pinboard's copy has changed since.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<Box<ExistingBookmark>>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExistingBookmark {
     pub url: String,
@@ -31,8 +45,32 @@ pub struct ExistingBookmark {
     pub private: bool,
     pub read_later: bool,
     pub time: String,
+    /// Pinboard's change signature (`meta=yes`); empty when not requested.
+    #[serde(default)]
+    pub meta: String,
 }
 
+impl ExistingBookmark {
+    /// A `replace=yes` payload that writes these fields back unchanged,
+    /// including the original date.
+    pub fn update_payload(&self) -> BookmarkPayload {
+        BookmarkPayload {
+            url: self.url.clone(),
+            title: self.title.clone(),
+            notes: self.notes.clone(),
+            tags: parse_tag_list(&self.tags),
+            private: self.private,
+            read_later: self.read_later,
+            intent: SubmitIntent::Update,
+            created_at: Some(self.time.clone()).filter(|time| !time.is_empty()),
+            base: None,
+        }
+    }
+}
+
+/// Placed between existing and appended notes by a merge.
+pub const NOTES_MERGE_SEPARATOR: &str = "\n\n---\n\n";
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DuplicateCheckResult {
@@ -40,13 +78,20 @@ pub struct DuplicateCheckResult {
     pub bookmark: Option<ExistingBookmark>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TagSuggestions {
     pub popular: Vec<String>,
     pub recommended: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTag {
+    pub tag: String,
+    pub count: i64,
+}
+
 pub fn normalize_url(input: &str) -> Option<String> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
@@ -62,6 +107,14 @@ pub fn normalize_url(input: &str) -> Option<String> {
     Url::parse(&candidate).ok().map(|url| url.to_string())
 }
 
+pub fn url_host(input: &str) -> Option<String> {
+    let normalized = normalize_url(input)?;
+    Url::parse(&normalized)
+        .ok()?
+        .host_str()
+        .map(|host| host.to_ascii_lowercase())
+}
+
 pub fn parse_tags(input: &str) -> Vec<String> {
     input
         .split_whitespace()
@@ -70,12 +123,15 @@ pub fn parse_tags(input: &str) -> Vec<String> {
         .collect()
 }
 
-pub fn merge_tags(existing: &[String], incoming: &[String]) -> Vec<String> {
+pub fn merge_tags<T: Clone + fmt::Display>(existing: &[T], incoming: &[T]) -> Vec<T> {
     let mut merged = Vec::new();
     let mut seen = HashSet::new();
 
     for tag in existing.iter().chain(incoming.iter()) {
-        let key = tag.to_lowercase();
+        let raw = tag.to_string();
+        let key = Tag::parse(&raw)
+            .map(|parsed| parsed.key())
+            .unwrap_or_else(|| raw.to_lowercase());
         if seen.insert(key) {
             merged.push(tag.clone());
         }
@@ -84,9 +140,57 @@ pub fn merge_tags(existing: &[String], incoming: &[String]) -> Vec<String> {
     merged
 }
 
+/// Folds an incoming capture into the bookmark already on Pinboard. The
+/// existing title wins unless it is empty, tags are unioned, new notes are
+/// appended unless already present, and flags only switch on.
+pub fn merge_payload(existing: &ExistingBookmark, incoming: &BookmarkPayload) -> BookmarkPayload {
+    let title = if existing.title.trim().is_empty() {
+        incoming.title.clone()
+    } else {
+        existing.title.clone()
+    };
+
+    let existing_notes = existing.notes.trim_end();
+    let incoming_notes = incoming.notes.trim();
+    let notes = if incoming_notes.is_empty() || existing_notes.contains(incoming_notes) {
+        existing.notes.clone()
+    } else if existing_notes.is_empty() {
+        incoming_notes.to_string()
+    } else {
+        format!("{existing_notes}{NOTES_MERGE_SEPARATOR}{incoming_notes}")
+    };
+
+    BookmarkPayload {
+        url: incoming.url.clone(),
+        title,
+        notes,
+        tags: merge_tags(&parse_tag_list(&existing.tags), &incoming.tags),
+        private: existing.private || incoming.private,
+        read_later: existing.read_later || incoming.read_later,
+        intent: SubmitIntent::Update,
+        created_at: Some(existing.time.clone()).filter(|time| !time.is_empty()),
+        base: None,
+    }
+}
+
+/// Formats an RFC 3339 timestamp the way `posts/add` expects `dt`
+/// (`CCYY-MM-DDThh:mm:ssZ`, UTC).
+pub fn pinboard_datetime(raw: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(raw.trim())
+        .ok()
+        .map(|time| {
+            time.with_timezone(&chrono::Utc)
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string()
+        })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{merge_tags, normalize_url};
+    use super::{
+        merge_payload, merge_tags, normalize_url, BookmarkPayload, ExistingBookmark, SubmitIntent,
+        Tag,
+    };
 
     #[test]
     fn normalize_url_adds_https() {
@@ -102,4 +206,40 @@ mod tests {
         );
         assert_eq!(merged, vec!["Tech", "Rust", "Arch"]);
     }
+
+    #[test]
+    fn merge_payload_unions_tags_and_appends_notes() {
+        let existing = ExistingBookmark {
+            url: "https://example.com/".to_string(),
+            title: "Original".to_string(),
+            notes: "first read".to_string(),
+            tags: vec!["rust".to_string(), "async".to_string()],
+            private: true,
+            read_later: false,
+            time: "2020-05-01T10:00:00Z".to_string(),
+            meta: String::new(),
+        };
+        let mut incoming = BookmarkPayload {
+            url: "https://example.com/".to_string(),
+            title: "Fetched title".to_string(),
+            notes: "second pass".to_string(),
+            tags: vec![Tag::parse("Rust").unwrap(), Tag::parse("tokio").unwrap()],
+            private: false,
+            read_later: true,
+            intent: SubmitIntent::Merge,
+            created_at: None,
+            base: None,
+        };
+
+        let merged = merge_payload(&existing, &incoming);
+        assert_eq!(merged.title, "Original");
+        assert_eq!(merged.notes, "first read\n\n---\n\nsecond pass");
+        assert_eq!(merged.tags, vec!["rust", "async", "tokio"]);
+        assert!(merged.private && merged.read_later);
+        assert!(matches!(merged.intent, SubmitIntent::Update));
+        assert_eq!(merged.created_at.as_deref(), Some("2020-05-01T10:00:00Z"));
+
+        incoming.notes = "first read".to_string();
+        assert_eq!(merge_payload(&existing, &incoming).notes, "first read");
+    }
 }
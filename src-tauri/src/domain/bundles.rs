@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::bookmark::{merge_tags, parse_tags};
+use crate::domain::tags::{Tag, TagRewrite};
+
+pub const BUNDLE_PREFIX: char = '@';
+
+/// A named tag set, expanded in the tag field by writing `@name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagBundle {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleExpansion {
+    pub tags: Vec<String>,
+    pub expanded: Vec<TagRewrite>,
+    /// `@` tokens that did not name a bundle; they are kept as plain tags.
+    pub unknown: Vec<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("bundle names cannot be empty")]
+    EmptyName,
+    #[error("bundle name `{0}` cannot contain spaces or commas")]
+    InvalidName(String),
+    #[error("bundle `{0}` is defined more than once")]
+    Duplicate(String),
+    #[error("bundle `{0}` has no tags")]
+    NoTags(String),
+    #[error("bundle `{0}` cannot include other bundles")]
+    Nested(String),
+}
+
+impl TagBundle {
+    /// Bundle name without the `@` prefix, as typed in the tag field.
+    fn key(&self) -> String {
+        self.name
+            .trim()
+            .trim_start_matches(BUNDLE_PREFIX)
+            .to_lowercase()
+    }
+}
+
+pub fn validate_bundles(bundles: &[TagBundle]) -> Result<(), BundleError> {
+    let mut seen = HashSet::new();
+    for bundle in bundles {
+        let key = bundle.key();
+        if key.is_empty() {
+            return Err(BundleError::EmptyName);
+        }
+        if key.contains(|ch: char| ch.is_whitespace() || ch == ',') {
+            return Err(BundleError::InvalidName(bundle.name.clone()));
+        }
+        if !seen.insert(key) {
+            return Err(BundleError::Duplicate(bundle.name.clone()));
+        }
+        let tags = parse_tags(&bundle.tags.join(" "));
+        if tags.is_empty() {
+            return Err(BundleError::NoTags(bundle.name.clone()));
+        }
+        if tags.iter().any(|tag| tag.starts_with(BUNDLE_PREFIX)) {
+            return Err(BundleError::Nested(bundle.name.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces every `@name` token with the tags of the matching bundle. Names
+/// match case-insensitively and other tokens pass through untouched, so tag
+/// normalization still runs on the result.
+pub fn expand_bundles(raw_tags: &[String], bundles: &[TagBundle]) -> BundleExpansion {
+    let mut expansion = BundleExpansion::default();
+
+    for token in parse_tags(&raw_tags.join(" ")) {
+        let Some(name) = bundle_reference(&token) else {
+            expansion.tags = merge_tags(&expansion.tags, &[token]);
+            continue;
+        };
+
+        match bundles.iter().find(|bundle| bundle.key() == name) {
+            Some(bundle) => {
+                let tags = parse_tags(&bundle.tags.join(" "));
+                expansion.expanded.push(TagRewrite {
+                    from: token,
+                    to: tags.join(" "),
+                });
+                expansion.tags = merge_tags(&expansion.tags, &tags);
+            }
+            None => {
+                expansion.unknown.push(token.clone());
+                expansion.tags = merge_tags(&expansion.tags, &[token]);
+            }
+        }
+    }
+
+    expansion
+}
+
+fn bundle_reference(token: &str) -> Option<String> {
+    let name = token
+        .strip_prefix(BUNDLE_PREFIX)?
+        .trim_end_matches([',', ';']);
+    Tag::parse(name).map(|_| name.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_bundles, validate_bundles, TagBundle};
+
+    fn bundle(name: &str, tags: &[&str]) -> TagBundle {
+        TagBundle {
+            name: name.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn expands_bundles_in_place_and_dedupes() {
+        let bundles = [bundle("rust-async", &["rust", "async", "tokio"])];
+        let expansion = expand_bundles(&tags(&["Rust", "@Rust-Async,", "howto"]), &bundles);
+
+        assert_eq!(expansion.tags, vec!["Rust", "async", "tokio", "howto"]);
+        assert_eq!(expansion.expanded[0].from, "@Rust-Async,");
+        assert_eq!(expansion.expanded[0].to, "rust async tokio");
+        assert!(expansion.unknown.is_empty());
+    }
+
+    #[test]
+    fn unknown_bundles_are_kept_and_reported() {
+        let expansion = expand_bundles(&tags(&["@missing", "@"]), &[]);
+        assert_eq!(expansion.tags, vec!["@missing", "@"]);
+        assert_eq!(expansion.unknown, vec!["@missing"]);
+    }
+
+    #[test]
+    fn validate_rejects_bad_bundles() {
+        assert!(validate_bundles(&[bundle("web", &["html", ".css"])]).is_ok());
+        assert!(validate_bundles(&[bundle("@", &["a"])]).is_err());
+        assert!(validate_bundles(&[bundle("a b", &["a"])]).is_err());
+        assert!(validate_bundles(&[bundle("a", &["x"]), bundle("@A", &["y"])]).is_err());
+        assert!(validate_bundles(&[bundle("empty", &[" "])]).is_err());
+        assert!(validate_bundles(&[bundle("outer", &["@inner"])]).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use serde::Serialize;
+
+use crate::domain::bookmark::{BookmarkPayload, ExistingBookmark};
+use crate::domain::tags::{tag_strings, Tag};
+
+/// One field of a three-way diff. `base` is what the update was based on,
+/// `remote` what Pinboard has now and `local` what we were about to write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub base: String,
+    pub remote: String,
+    pub local: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkConflict {
+    pub url: String,
+    pub base: ExistingBookmark,
+    /// `None` when the bookmark was deleted elsewhere.
+    pub remote: Option<ExistingBookmark>,
+    pub local: BookmarkPayload,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Whether `remote` differs from the version an update was based on. Uses
+/// Pinboard's change signature (`meta`) when both sides have one and falls
+/// back to comparing the editable fields.
+pub fn changed_since(base: &ExistingBookmark, remote: &ExistingBookmark) -> bool {
+    if !base.meta.is_empty() && !remote.meta.is_empty() {
+        return base.meta != remote.meta;
+    }
+    field_values(base) != field_values(remote)
+}
+
+pub fn build_conflict(
+    base: &ExistingBookmark,
+    remote: Option<&ExistingBookmark>,
+    local: &BookmarkPayload,
+) -> BookmarkConflict {
+    let base_values = field_values(base);
+    let remote_values = remote.map(field_values);
+    let local_values = payload_values(local);
+
+    let changes = base_values
+        .iter()
+        .zip(local_values.iter())
+        .enumerate()
+        .filter_map(|(index, ((field, base_value), (_, local_value)))| {
+            let remote_value = remote_values
+                .as_ref()
+                .map(|values| values[index].1.clone())
+                .unwrap_or_default();
+            let differs =
+                remote_values.is_none() || &remote_value != base_value || local_value != base_value;
+            differs.then(|| FieldChange {
+                field: field.to_string(),
+                base: base_value.clone(),
+                remote: remote_value,
+                local: local_value.clone(),
+            })
+        })
+        .collect();
+
+    BookmarkConflict {
+        url: local.url.clone(),
+        base: base.clone(),
+        remote: remote.cloned(),
+        local: local.clone(),
+        changes,
+    }
+}
+
+fn field_values(bookmark: &ExistingBookmark) -> Vec<(&'static str, String)> {
+    values(
+        &bookmark.title,
+        &bookmark.notes,
+        &bookmark.tags,
+        bookmark.private,
+        bookmark.read_later,
+    )
+}
+
+fn payload_values(payload: &BookmarkPayload) -> Vec<(&'static str, String)> {
+    values(
+        &payload.title,
+        &payload.notes,
+        &tag_strings(&payload.tags),
+        payload.private,
+        payload.read_later,
+    )
+}
+
+fn values(
+    title: &str,
+    notes: &str,
+    tags: &[String],
+    private: bool,
+    read_later: bool,
+) -> Vec<(&'static str, String)> {
+    let mut tag_keys = tags
+        .iter()
+        .filter_map(|tag| Tag::parse(tag))
+        .map(|tag| tag.to_string())
+        .collect::<Vec<_>>();
+    tag_keys.sort_by_key(|tag| tag.to_lowercase());
+
+    vec![
+        ("title", title.trim().to_string()),
+        ("notes", notes.trim().to_string()),
+        ("tags", tag_keys.join(" ")),
+        ("private", private.to_string()),
+        ("readLater", read_later.to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_conflict, changed_since, Tag};
+    use crate::domain::bookmark::ExistingBookmark;
+
+    fn bookmark(title: &str, tags: &[&str], meta: &str) -> ExistingBookmark {
+        ExistingBookmark {
+            url: "https://example.com/".to_string(),
+            title: title.to_string(),
+            notes: String::new(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            private: false,
+            read_later: false,
+            time: "2020-01-01T00:00:00Z".to_string(),
+            meta: meta.to_string(),
+        }
+    }
+
+    #[test]
+    fn meta_signature_wins_over_field_comparison() {
+        let base = bookmark("A", &["x"], "m1");
+        assert!(!changed_since(&base, &bookmark("A", &["x"], "m1")));
+        assert!(changed_since(&base, &bookmark("A", &["x"], "m2")));
+        assert!(changed_since(
+            &bookmark("A", &["x"], ""),
+            &bookmark("B", &["x"], "m1")
+        ));
+        assert!(!changed_since(
+            &bookmark("A", &["b", "a"], ""),
+            &bookmark("A", &["a", "b"], "")
+        ));
+    }
+
+    #[test]
+    fn diff_lists_fields_changed_on_either_side() {
+        let base = bookmark("Original", &["x"], "m1");
+        let remote = bookmark("Edited on phone", &["x"], "m2");
+        let mut local = base.update_payload();
+        local.tags = vec![Tag::parse("x").unwrap(), Tag::parse("y").unwrap()];
+
+        let conflict = build_conflict(&base, Some(&remote), &local);
+        let fields = conflict
+            .changes
+            .iter()
+            .map(|change| change.field.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["title", "tags"]);
+        assert_eq!(conflict.changes[0].remote, "Edited on phone");
+        assert_eq!(conflict.changes[1].local, "x y");
+
+        let deleted = build_conflict(&base, None, &local);
+        assert_eq!(deleted.changes.len(), 5);
+    }
+}
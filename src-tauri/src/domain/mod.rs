@@ -1 +1,7 @@
+pub mod accounts;
 pub mod bookmark;
+pub mod bundles;
+pub mod conflict;
+pub mod rules;
+pub mod tags;
+pub mod validation;
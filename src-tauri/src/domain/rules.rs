@@ -0,0 +1,332 @@
+use std::cmp::Reverse;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::domain::bookmark::{merge_tags, normalize_url, BookmarkPayload};
+use crate::domain::tags::parse_tag_list;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternKind {
+    #[default]
+    Glob,
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitleRewrite {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// A per-domain rule. `host` and `path` are matched as globs (`*`, `?`) or
+/// regexes depending on `kind`; host matching is case-insensitive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainRule {
+    pub name: String,
+    #[serde(default)]
+    pub kind: PatternKind,
+    pub host: String,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub private: bool,
+    #[serde(default)]
+    pub read_later: bool,
+    #[serde(default)]
+    pub title_rewrite: Option<TitleRewrite>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOutcome {
+    pub matched_rules: Vec<String>,
+    pub add_tags: Vec<String>,
+    pub private: bool,
+    pub read_later: bool,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RuleError {
+    #[error("rule \"{rule}\" has an empty host pattern")]
+    EmptyHost { rule: String },
+    #[error("rule \"{rule}\" has an invalid pattern `{pattern}`: {message}")]
+    InvalidPattern {
+        rule: String,
+        pattern: String,
+        message: String,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+struct CompiledRule<'a> {
+    rule: &'a DomainRule,
+    host: Regex,
+    path: Option<Regex>,
+    title_rewrite: Option<(Regex, &'a str)>,
+}
+
+pub fn validate_rules(rules: &[DomainRule]) -> Result<(), RuleError> {
+    for rule in rules {
+        compile_rule(rule)?;
+    }
+    Ok(())
+}
+
+/// Evaluates every enabled rule against `url`. Rules are applied by
+/// descending `priority`, ties keeping declaration order: tags accumulate
+/// (deduplicated), `private`/`read_later` are set if any match sets them, and
+/// only the first matching rule with a title rewrite changes the title.
+pub fn evaluate_rules(rules: &[DomainRule], url: &str, title: &str) -> RuleOutcome {
+    let mut outcome = RuleOutcome::default();
+
+    let Some(parsed) = normalize_url(url).and_then(|normalized| Url::parse(&normalized).ok())
+    else {
+        return outcome;
+    };
+    let host = parsed.host_str().unwrap_or("");
+    let path = parsed.path();
+
+    let mut compiled = rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| compile_rule(rule).ok())
+        .collect::<Vec<_>>();
+    compiled.sort_by_key(|entry| Reverse(entry.rule.priority));
+
+    for entry in compiled {
+        if !entry.host.is_match(host) {
+            continue;
+        }
+        if let Some(path_pattern) = &entry.path {
+            if !path_pattern.is_match(path) {
+                continue;
+            }
+        }
+
+        outcome.matched_rules.push(entry.rule.name.clone());
+        outcome.add_tags = merge_tags(&outcome.add_tags, &entry.rule.add_tags);
+        outcome.private |= entry.rule.private;
+        outcome.read_later |= entry.rule.read_later;
+
+        if outcome.title.is_none() {
+            if let Some((pattern, replacement)) = &entry.title_rewrite {
+                if pattern.is_match(title) {
+                    outcome.title =
+                        Some(pattern.replace_all(title, *replacement).trim().to_string());
+                }
+            }
+        }
+    }
+
+    outcome
+}
+
+/// Applies a rule outcome on top of user input. Rules only ever add tags and
+/// turn flags on, so an explicit user choice is never switched off.
+pub fn apply_rule_outcome(payload: &mut BookmarkPayload, outcome: &RuleOutcome) {
+    payload.tags = merge_tags(&payload.tags, &parse_tag_list(&outcome.add_tags));
+    payload.private |= outcome.private;
+    payload.read_later |= outcome.read_later;
+    if let Some(title) = outcome.title.as_ref().filter(|title| !title.is_empty()) {
+        payload.title = title.clone();
+    }
+}
+
+fn compile_rule(rule: &DomainRule) -> Result<CompiledRule<'_>, RuleError> {
+    if rule.host.trim().is_empty() {
+        return Err(RuleError::EmptyHost {
+            rule: rule.name.clone(),
+        });
+    }
+
+    let host = compile_pattern(rule, rule.kind, &rule.host, true)?;
+    let path = match rule.path.as_deref().map(str::trim) {
+        Some(pattern) if !pattern.is_empty() => {
+            Some(compile_pattern(rule, rule.kind, pattern, false)?)
+        }
+        _ => None,
+    };
+    let title_rewrite = match &rule.title_rewrite {
+        Some(rewrite) => Some((
+            compile_pattern(rule, PatternKind::Regex, &rewrite.pattern, false)?,
+            rewrite.replacement.as_str(),
+        )),
+        None => None,
+    };
+
+    Ok(CompiledRule {
+        rule,
+        host,
+        path,
+        title_rewrite,
+    })
+}
+
+fn compile_pattern(
+    rule: &DomainRule,
+    kind: PatternKind,
+    pattern: &str,
+    case_insensitive: bool,
+) -> Result<Regex, RuleError> {
+    let source = match kind {
+        PatternKind::Glob => glob_to_regex(pattern.trim()),
+        PatternKind::Regex => pattern.to_string(),
+    };
+
+    RegexBuilder::new(&source)
+        .case_insensitive(case_insensitive)
+        .build()
+        .map_err(|e| RuleError::InvalidPattern {
+            rule: rule.name.clone(),
+            pattern: pattern.to_string(),
+            message: e.to_string(),
+        })
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut source = String::from("^");
+    for ch in glob.chars() {
+        match ch {
+            '*' => source.push_str(".*"),
+            '?' => source.push('.'),
+            other => source.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    source.push('$');
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_rule_outcome, evaluate_rules, validate_rules, DomainRule, PatternKind, TitleRewrite,
+    };
+    use crate::domain::bookmark::{BookmarkPayload, SubmitIntent};
+    use crate::domain::tags::Tag;
+
+    fn rule(name: &str, host: &str) -> DomainRule {
+        DomainRule {
+            name: name.to_string(),
+            kind: PatternKind::Glob,
+            host: host.to_string(),
+            path: None,
+            priority: 0,
+            add_tags: Vec::new(),
+            private: false,
+            read_later: false,
+            title_rewrite: None,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn glob_host_and_path_match() {
+        let mut code = rule("code", "github.com");
+        code.add_tags = vec!["code".to_string()];
+        let mut wiki = rule("wiki", "*.wiki.internal");
+        wiki.path = Some("/team/*".to_string());
+        wiki.private = true;
+
+        let rules = vec![code, wiki];
+        let github = evaluate_rules(&rules, "https://GitHub.com/rust-lang/rust", "");
+        assert_eq!(github.add_tags, vec!["code"]);
+        assert!(!github.private);
+
+        let wiki_page = evaluate_rules(&rules, "docs.wiki.internal/team/onboarding", "");
+        assert_eq!(wiki_page.matched_rules, vec!["wiki"]);
+        assert!(wiki_page.private);
+
+        let other_path = evaluate_rules(&rules, "https://docs.wiki.internal/public", "");
+        assert!(other_path.matched_rules.is_empty());
+    }
+
+    #[test]
+    fn regex_rules_match_host() {
+        let mut video = rule("video", r"^(www\.)?youtube\.com$");
+        video.kind = PatternKind::Regex;
+        video.read_later = true;
+
+        let outcome = evaluate_rules(&[video], "https://www.youtube.com/watch?v=1", "");
+        assert!(outcome.read_later);
+    }
+
+    #[test]
+    fn precedence_is_priority_then_declaration_order() {
+        let mut first = rule("first", "*.example.com");
+        first.add_tags = vec!["a".to_string()];
+        first.title_rewrite = Some(TitleRewrite {
+            pattern: " - Example$".to_string(),
+            replacement: String::new(),
+        });
+        let mut second = rule("second", "blog.example.com");
+        second.add_tags = vec!["b".to_string(), "A".to_string()];
+        second.title_rewrite = Some(TitleRewrite {
+            pattern: "^Blog: ".to_string(),
+            replacement: String::new(),
+        });
+        let mut urgent = rule("urgent", "blog.example.com");
+        urgent.priority = 10;
+        urgent.add_tags = vec!["c".to_string()];
+
+        let outcome = evaluate_rules(
+            &[first, second, urgent],
+            "https://blog.example.com/post",
+            "Blog: Post - Example",
+        );
+        assert_eq!(outcome.matched_rules, vec!["urgent", "first", "second"]);
+        assert_eq!(outcome.add_tags, vec!["c", "a", "b"]);
+        assert_eq!(outcome.title.as_deref(), Some("Blog: Post"));
+    }
+
+    #[test]
+    fn disabled_and_invalid_rules_are_skipped() {
+        let mut disabled = rule("disabled", "*");
+        disabled.enabled = false;
+        disabled.private = true;
+        let mut broken = rule("broken", "(");
+        broken.kind = PatternKind::Regex;
+        broken.private = true;
+
+        assert!(validate_rules(&[broken.clone()]).is_err());
+        let outcome = evaluate_rules(&[disabled, broken], "https://example.com", "");
+        assert!(!outcome.private);
+    }
+
+    #[test]
+    fn apply_outcome_only_adds() {
+        let mut payload = BookmarkPayload {
+            url: "https://github.com/".to_string(),
+            title: "GitHub".to_string(),
+            notes: String::new(),
+            tags: vec![Tag::parse("Code").unwrap()],
+            private: true,
+            read_later: false,
+            intent: SubmitIntent::Create,
+            created_at: None,
+            base: None,
+        };
+        let mut code = rule("code", "github.com");
+        code.add_tags = vec!["code".to_string(), "dev".to_string()];
+
+        let outcome = evaluate_rules(&[code], &payload.url, &payload.title);
+        apply_rule_outcome(&mut payload, &outcome);
+        assert_eq!(payload.tags, vec!["Code", "dev"]);
+        assert!(payload.private);
+        assert_eq!(payload.title, "GitHub");
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::bookmark::{merge_tags, parse_tags};
+
+const TRAILING_PUNCTUATION: &[char] = &[',', ';', ':', '.', '!', '?'];
+const PRIVATE_TAG_PREFIX: char = '.';
+
+/// A Pinboard tag. Tags written with a leading `.` are private: Pinboard only
+/// shows them to the owner, so `rust` and `.rust` are distinct tags.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Tag {
+    name: String,
+    private: bool,
+}
+
+impl Tag {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let trimmed = raw.trim();
+        let (name, private) = match trimmed.strip_prefix(PRIVATE_TAG_PREFIX) {
+            Some(name) => (name, true),
+            None => (trimmed, false),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            private,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+
+    pub fn into_private(self) -> Self {
+        Self {
+            private: true,
+            ..self
+        }
+    }
+
+    /// Case-insensitive identity used for deduplication.
+    pub fn key(&self) -> String {
+        let name = self.name.to_lowercase();
+        if self.private {
+            format!("{PRIVATE_TAG_PREFIX}{name}")
+        } else {
+            name
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.private {
+            write!(f, "{PRIVATE_TAG_PREFIX}{}", self.name)
+        } else {
+            f.write_str(&self.name)
+        }
+    }
+}
+
+impl TryFrom<String> for Tag {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("invalid tag `{value}`"))
+    }
+}
+
+impl From<Tag> for String {
+    fn from(tag: Tag) -> Self {
+        tag.to_string()
+    }
+}
+
+impl PartialEq<&str> for Tag {
+    fn eq(&self, other: &&str) -> bool {
+        match other.strip_prefix(PRIVATE_TAG_PREFIX) {
+            Some(name) => self.private && self.name == name,
+            None => !self.private && self.name == *other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaseFolding {
+    #[default]
+    Preserve,
+    Lowercase,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagNormalization {
+    #[serde(default)]
+    pub case_folding: CaseFolding,
+    #[serde(default = "default_true")]
+    pub strip_trailing_punctuation: bool,
+    #[serde(default = "default_true")]
+    pub split_on_commas: bool,
+    /// Alias → canonical tag. Keys are matched case-insensitively.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Tag names that are always sent as private (`.name`).
+    #[serde(default)]
+    pub private_tags: Vec<String>,
+}
+
+impl Default for TagNormalization {
+    fn default() -> Self {
+        Self {
+            case_folding: CaseFolding::Preserve,
+            strip_trailing_punctuation: true,
+            split_on_commas: true,
+            aliases: BTreeMap::new(),
+            private_tags: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPreview {
+    pub tags: Vec<String>,
+    pub rewrites: Vec<TagRewrite>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TagNormalizationError {
+    #[error("alias entries must have a non-empty name and target")]
+    EmptyAlias,
+    #[error("alias target `{0}` must be a single tag")]
+    InvalidAliasTarget(String),
+    #[error("alias `{0}` is listed more than once; aliases ignore case")]
+    DuplicateAlias(String),
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl TagNormalization {
+    pub fn validate(&self) -> Result<(), TagNormalizationError> {
+        let mut seen = HashSet::new();
+        for (alias, target) in &self.aliases {
+            if alias.trim().is_empty() || target.trim().is_empty() {
+                return Err(TagNormalizationError::EmptyAlias);
+            }
+            // Lookups ignore ASCII case, so `JS` and `js` would shadow each other.
+            if !seen.insert(alias.trim().to_ascii_lowercase()) {
+                return Err(TagNormalizationError::DuplicateAlias(alias.clone()));
+            }
+            if target.trim().contains(char::is_whitespace) {
+                return Err(TagNormalizationError::InvalidAliasTarget(target.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    fn lookup_alias(&self, tag: &str) -> Option<&str> {
+        self.aliases
+            .iter()
+            .find(|(alias, _)| alias.trim().eq_ignore_ascii_case(tag))
+            .map(|(_, target)| target.trim())
+    }
+
+    fn is_forced_private(&self, tag: &Tag) -> bool {
+        self.private_tags.iter().any(|name| {
+            name.trim()
+                .trim_start_matches(PRIVATE_TAG_PREFIX)
+                .eq_ignore_ascii_case(tag.name())
+        })
+    }
+
+    fn normalize_one(&self, raw: &str) -> Option<String> {
+        let mut raw = raw.trim();
+        if self.strip_trailing_punctuation {
+            raw = raw.trim_end_matches(TRAILING_PUNCTUATION);
+        }
+        let tag = Tag::parse(raw)?;
+
+        let aliased = self.lookup_alias(tag.name()).unwrap_or(tag.name());
+        let folded = match self.case_folding {
+            CaseFolding::Preserve => aliased.to_string(),
+            CaseFolding::Lowercase => aliased.to_lowercase(),
+        };
+
+        let mut normalized = Tag::parse(&folded)?;
+        if tag.is_private() || self.is_forced_private(&normalized) {
+            normalized = normalized.into_private();
+        }
+        Some(normalized.to_string())
+    }
+}
+
+/// Splits raw tag input into tokens, honouring commas when enabled.
+pub fn tokenize_tags(input: &str, settings: &TagNormalization) -> Vec<String> {
+    if settings.split_on_commas {
+        parse_tags(&input.replace(',', " "))
+    } else {
+        parse_tags(input)
+    }
+}
+
+/// Produces the tags that will actually be sent, plus every token that was
+/// rewritten on the way (punctuation, alias or case changes).
+pub fn preview_tags(raw_tags: &[String], settings: &TagNormalization) -> TagPreview {
+    let mut preview = TagPreview::default();
+
+    for token in tokenize_tags(&raw_tags.join(" "), settings) {
+        let Some(normalized) = settings.normalize_one(&token) else {
+            continue;
+        };
+        if normalized != token {
+            preview.rewrites.push(TagRewrite {
+                from: token,
+                to: normalized.clone(),
+            });
+        }
+        preview.tags = merge_tags(&preview.tags, &[normalized]);
+    }
+
+    preview
+}
+
+pub fn normalize_tags(raw_tags: &[String], settings: &TagNormalization) -> Vec<Tag> {
+    parse_tag_list(&preview_tags(raw_tags, settings).tags)
+}
+
+/// Parses raw tokens into tags, dropping any that are empty.
+pub fn parse_tag_list(raw_tags: &[String]) -> Vec<Tag> {
+    raw_tags.iter().filter_map(|raw| Tag::parse(raw)).collect()
+}
+
+/// The tags as Pinboard writes them, private ones with their leading `.`.
+pub fn tag_strings(tags: &[Tag]) -> Vec<String> {
+    tags.iter().map(ToString::to_string).collect()
+}
+
+/// Drops private tags so they never appear in lists that mix in public data.
+pub fn strip_private_tags(tags: &[String]) -> Vec<String> {
+    tags.iter()
+        .filter(|tag| Tag::parse(tag).is_some_and(|tag| !tag.is_private()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        normalize_tags, preview_tags, strip_private_tags, CaseFolding, Tag, TagNormalization,
+        TagNormalizationError, TagRewrite,
+    };
+
+    fn tags(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn default_settings_split_commas_and_strip_punctuation() {
+        let normalized = normalize_tags(
+            &tags(&["rust,async", "tokio;", "Rust"]),
+            &TagNormalization::default(),
+        );
+        assert_eq!(normalized, vec!["rust", "async", "tokio"]);
+    }
+
+    #[test]
+    fn aliases_and_case_folding_collapse_variants() {
+        let mut settings = TagNormalization {
+            case_folding: CaseFolding::Lowercase,
+            ..TagNormalization::default()
+        };
+        settings
+            .aliases
+            .insert("js".to_string(), "javascript".to_string());
+
+        let preview = preview_tags(&tags(&["JS", "javascript", "Javascript", "js,"]), &settings);
+        assert_eq!(preview.tags, vec!["javascript"]);
+        assert_eq!(
+            preview.rewrites[0],
+            TagRewrite {
+                from: "JS".to_string(),
+                to: "javascript".to_string(),
+            }
+        );
+        assert_eq!(preview.rewrites.len(), 3);
+    }
+
+    #[test]
+    fn commas_are_kept_when_splitting_is_disabled() {
+        let settings = TagNormalization {
+            split_on_commas: false,
+            strip_trailing_punctuation: false,
+            ..TagNormalization::default()
+        };
+        assert_eq!(normalize_tags(&tags(&["a,b"]), &settings), vec!["a,b"]);
+    }
+
+    #[test]
+    fn tag_parses_private_prefix() {
+        let tag = Tag::parse(".Reading").unwrap();
+        assert!(tag.is_private());
+        assert_eq!(tag.name(), "Reading");
+        assert_eq!(tag.key(), ".reading");
+        assert_eq!(tag.to_string(), ".Reading");
+        assert!(Tag::parse(".").is_none());
+    }
+
+    #[test]
+    fn private_tags_survive_normalization_and_can_be_forced() {
+        let mut settings = TagNormalization {
+            private_tags: vec!["finance".to_string()],
+            ..TagNormalization::default()
+        };
+        settings
+            .aliases
+            .insert("money".to_string(), "finance".to_string());
+
+        let normalized = normalize_tags(
+            &tags(&[".todo", "money", "Finance", "rust", ".rust"]),
+            &settings,
+        );
+        assert_eq!(normalized, vec![".todo", ".finance", "rust", ".rust"]);
+    }
+
+    #[test]
+    fn strip_private_tags_keeps_public_only() {
+        assert_eq!(
+            strip_private_tags(&tags(&["rust", ".secret", "."])),
+            vec!["rust"]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_multi_word_alias_targets() {
+        let mut settings = TagNormalization::default();
+        settings
+            .aliases
+            .insert("ml".to_string(), "machine learning".to_string());
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn validate_rejects_aliases_differing_only_in_case() {
+        let mut settings = TagNormalization::default();
+        settings
+            .aliases
+            .insert("JS".to_string(), "javascript".to_string());
+        settings
+            .aliases
+            .insert(" js".to_string(), "ecmascript".to_string());
+        assert!(matches!(
+            settings.validate(),
+            Err(TagNormalizationError::DuplicateAlias(_))
+        ));
+    }
+}
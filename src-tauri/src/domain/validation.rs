@@ -0,0 +1,166 @@
+use serde::Serialize;
+
+use crate::domain::bookmark::{pinboard_datetime, BookmarkPayload};
+use crate::domain::tags::Tag;
+
+pub const MAX_TITLE_CHARS: usize = 255;
+pub const MAX_NOTES_CHARS: usize = 65_536;
+pub const MAX_TAG_CHARS: usize = 255;
+pub const MAX_TAG_COUNT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BookmarkField {
+    Title,
+    Notes,
+    Tags,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: BookmarkField,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: BookmarkField, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+}
+
+/// Checks a payload against Pinboard's field limits so bad input is rejected
+/// locally instead of after a `posts/add` round trip.
+pub fn validate_payload(payload: &BookmarkPayload) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    let title_len = payload.title.trim().chars().count();
+    if title_len == 0 {
+        errors.push(FieldError::new(BookmarkField::Title, "Title is required"));
+    } else if title_len > MAX_TITLE_CHARS {
+        errors.push(FieldError::new(
+            BookmarkField::Title,
+            format!("Title is {title_len} characters; Pinboard allows {MAX_TITLE_CHARS}"),
+        ));
+    }
+
+    let notes_len = payload.notes.chars().count();
+    if notes_len > MAX_NOTES_CHARS {
+        errors.push(FieldError::new(
+            BookmarkField::Notes,
+            format!("Notes are {notes_len} characters; Pinboard allows {MAX_NOTES_CHARS}"),
+        ));
+    }
+
+    if payload.tags.len() > MAX_TAG_COUNT {
+        errors.push(FieldError::new(
+            BookmarkField::Tags,
+            format!(
+                "{} tags given; Pinboard allows {MAX_TAG_COUNT}",
+                payload.tags.len()
+            ),
+        ));
+    }
+
+    for tag in &payload.tags {
+        if let Some(message) = validate_tag(&tag.to_string()) {
+            errors.push(FieldError::new(BookmarkField::Tags, message));
+        }
+    }
+
+    if let Some(created_at) = &payload.created_at {
+        if pinboard_datetime(created_at).is_none() {
+            errors.push(FieldError::new(
+                BookmarkField::CreatedAt,
+                format!("`{created_at}` is not an RFC 3339 timestamp"),
+            ));
+        }
+    }
+
+    errors
+}
+
+pub fn validate_tag(tag: &str) -> Option<String> {
+    if Tag::parse(tag).is_none() {
+        return Some("Tags cannot be empty".to_string());
+    }
+    if tag.contains(',') {
+        return Some(format!("Tag `{tag}` cannot contain commas"));
+    }
+    if tag.chars().any(char::is_whitespace) {
+        return Some(format!("Tag `{tag}` cannot contain spaces"));
+    }
+    let len = tag.chars().count();
+    if len > MAX_TAG_CHARS {
+        return Some(format!(
+            "Tag `{}...` is {len} characters; Pinboard allows {MAX_TAG_CHARS}",
+            tag.chars().take(24).collect::<String>()
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_payload, validate_tag, BookmarkField, Tag, MAX_TAG_COUNT};
+    use crate::domain::bookmark::{BookmarkPayload, SubmitIntent};
+
+    fn payload() -> BookmarkPayload {
+        BookmarkPayload {
+            url: "https://example.com/".to_string(),
+            title: "Example".to_string(),
+            notes: String::new(),
+            tags: vec![Tag::parse("rust").unwrap(), Tag::parse(".private").unwrap()],
+            private: false,
+            read_later: false,
+            intent: SubmitIntent::Create,
+            created_at: None,
+            base: None,
+        }
+    }
+
+    #[test]
+    fn valid_payload_has_no_errors() {
+        assert!(validate_payload(&payload()).is_empty());
+    }
+
+    #[test]
+    fn reports_each_field() {
+        let mut invalid = payload();
+        invalid.title = "x".repeat(256);
+        invalid.notes = "n".repeat(65_537);
+        invalid.tags = vec![Tag::parse("a,b").unwrap(), Tag::parse("a b").unwrap()];
+        invalid.created_at = Some("yesterday".to_string());
+
+        let fields = validate_payload(&invalid)
+            .into_iter()
+            .map(|error| error.field)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                BookmarkField::Title,
+                BookmarkField::Notes,
+                BookmarkField::Tags,
+                BookmarkField::Tags,
+                BookmarkField::CreatedAt,
+            ]
+        );
+    }
+
+    #[test]
+    fn tag_count_and_length_are_limited() {
+        let mut invalid = payload();
+        invalid.tags = (0..=MAX_TAG_COUNT)
+            .filter_map(|i| Tag::parse(&format!("t{i}")))
+            .collect();
+        assert_eq!(validate_payload(&invalid).len(), 1);
+
+        assert!(validate_tag(&"t".repeat(256)).is_some());
+        assert!(validate_tag(&"t".repeat(255)).is_none());
+    }
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::domain::bookmark::ExistingBookmark;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    /// Netscape bookmark HTML, readable by browsers and Pinboard's importer.
+    Netscape,
+    /// The `posts/all` JSON shape.
+    PinboardJson,
+    Csv,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Netscape => "html",
+            Self::PinboardJson => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+pub fn render_export(bookmarks: &[ExistingBookmark], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Netscape => render_netscape(bookmarks),
+        ExportFormat::PinboardJson => render_pinboard_json(bookmarks),
+        ExportFormat::Csv => render_csv(bookmarks),
+        ExportFormat::Markdown => render_markdown(bookmarks),
+    }
+}
+
+fn render_netscape(bookmarks: &[ExistingBookmark]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+
+    for bookmark in bookmarks {
+        let add_date = unix_time(&bookmark.time)
+            .map(|secs| format!(" ADD_DATE=\"{secs}\""))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "<DT><A HREF=\"{}\"{add_date} PRIVATE=\"{}\" TOREAD=\"{}\" TAGS=\"{}\">{}</A>\n",
+            escape_html(&bookmark.url),
+            u8::from(bookmark.private),
+            u8::from(bookmark.read_later),
+            escape_html(&bookmark.tags.join(",")),
+            escape_html(&bookmark.title),
+        ));
+        if !bookmark.notes.trim().is_empty() {
+            out.push_str(&format!("<DD>{}\n", escape_html(bookmark.notes.trim())));
+        }
+    }
+
+    out.push_str("</DL><p>\n");
+    out
+}
+
+fn render_pinboard_json(bookmarks: &[ExistingBookmark]) -> String {
+    let posts = bookmarks
+        .iter()
+        .map(|bookmark| {
+            json!({
+                "href": bookmark.url,
+                "description": bookmark.title,
+                "extended": bookmark.notes,
+                "meta": bookmark.meta,
+                "time": bookmark.time,
+                "shared": if bookmark.private { "no" } else { "yes" },
+                "toread": if bookmark.read_later { "yes" } else { "no" },
+                "tags": bookmark.tags.join(" "),
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&posts).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn render_csv(bookmarks: &[ExistingBookmark]) -> String {
+    let mut out = String::from("url,title,notes,tags,time,private,toread\n");
+    for bookmark in bookmarks {
+        let row = [
+            bookmark.url.clone(),
+            bookmark.title.clone(),
+            bookmark.notes.clone(),
+            bookmark.tags.join(" "),
+            bookmark.time.clone(),
+            bookmark.private.to_string(),
+            bookmark.read_later.to_string(),
+        ];
+        out.push_str(
+            &row.iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+/// `- [title](url)` per bookmark, followed by date, tags and flags, with
+/// notes as an indented quote.
+fn render_markdown(bookmarks: &[ExistingBookmark]) -> String {
+    let mut out = String::from("# Bookmarks\n\n");
+    for bookmark in bookmarks {
+        let title = if bookmark.title.trim().is_empty() {
+            &bookmark.url
+        } else {
+            &bookmark.title
+        };
+        let mut details = Vec::new();
+        if let Some(date) = bookmark.time.get(..10).filter(|date| !date.is_empty()) {
+            details.push(date.to_string());
+        }
+        if !bookmark.tags.is_empty() {
+            details.push(
+                bookmark
+                    .tags
+                    .iter()
+                    .map(|tag| format!("`{tag}`"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        if bookmark.private {
+            details.push("private".to_string());
+        }
+        if bookmark.read_later {
+            details.push("to read".to_string());
+        }
+
+        out.push_str(&format!(
+            "- [{}](<{}>)",
+            escape_markdown(title.trim()),
+            bookmark.url.replace('>', "%3E")
+        ));
+        if !details.is_empty() {
+            out.push_str(&format!(" · {}", details.join(" · ")));
+        }
+        out.push('\n');
+        for line in bookmark.notes.trim().lines() {
+            out.push_str(&format!("  > {line}\n"));
+        }
+    }
+    out
+}
+
+fn unix_time(time: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(time.trim())
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.chars()
+        .flat_map(|ch| match ch {
+            '[' | ']' | '\\' | '*' | '_' | '`' => vec!['\\', ch],
+            '\n' => vec![' '],
+            ch => vec![ch],
+        })
+        .collect()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_export, ExportFormat};
+    use crate::domain::bookmark::ExistingBookmark;
+    use crate::domain::tags::tag_strings;
+    use crate::import::formats::{detect_format, parse_import, ImportFormat};
+
+    fn collection() -> Vec<ExistingBookmark> {
+        vec![
+            ExistingBookmark {
+                url: "https://example.com/a?x=1&y=\"2\"".to_string(),
+                title: "Tricky <title>, with \"quotes\"".to_string(),
+                notes: "line one\nline two, with comma".to_string(),
+                tags: vec!["rust".to_string(), ".private".to_string()],
+                private: true,
+                read_later: true,
+                time: "2023-11-14T22:13:20Z".to_string(),
+                meta: "abc".to_string(),
+            },
+            ExistingBookmark {
+                url: "https://example.org/".to_string(),
+                title: "Plain".to_string(),
+                notes: String::new(),
+                tags: Vec::new(),
+                private: false,
+                read_later: false,
+                time: "2020-01-02T03:04:05Z".to_string(),
+                meta: String::new(),
+            },
+        ]
+    }
+
+    #[test]
+    fn exports_round_trip_through_the_import_parsers() {
+        let bookmarks = collection();
+        for (export, import) in [
+            (ExportFormat::Netscape, ImportFormat::Netscape),
+            (ExportFormat::PinboardJson, ImportFormat::Json),
+            (ExportFormat::Csv, ImportFormat::Csv),
+        ] {
+            let rendered = render_export(&bookmarks, export);
+            assert_eq!(detect_format(&rendered), import, "{export:?}");
+
+            let parsed = parse_import(&rendered, import).unwrap();
+            assert_eq!(parsed.len(), bookmarks.len(), "{export:?}");
+            for (original, payload) in bookmarks.iter().zip(&parsed) {
+                assert_eq!(payload.url, original.url, "{export:?}");
+                assert_eq!(payload.title, original.title, "{export:?}");
+                assert_eq!(payload.notes, original.notes, "{export:?}");
+                assert_eq!(tag_strings(&payload.tags), original.tags, "{export:?}");
+                assert_eq!(payload.private, original.private, "{export:?}");
+                assert_eq!(payload.read_later, original.read_later, "{export:?}");
+                assert_eq!(
+                    payload.created_at.as_deref(),
+                    Some(original.time.as_str()),
+                    "{export:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn markdown_lists_links_with_details() {
+        let rendered = render_export(&collection(), ExportFormat::Markdown);
+        assert!(rendered.contains(
+            "- [Tricky <title>, with \"quotes\"](<https://example.com/a?x=1&y=\"2\">) · 2023-11-14 · `rust` `.private` · private · to read\n  > line one\n  > line two, with comma\n"
+        ));
+        assert!(rendered.contains("- [Plain](<https://example.org/>) · 2020-01-02\n"));
+    }
+}
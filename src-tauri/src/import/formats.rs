@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::bookmark::{BookmarkPayload, SubmitIntent};
+use crate::domain::tags::Tag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+    /// Netscape bookmark HTML: Firefox, Chrome and Pinboard exports, and
+    /// Pocket's `ril_export.html`.
+    Netscape,
+    /// CSV with a header row: Pocket, Instapaper and Raindrop exports.
+    Csv,
+    /// Pinboard JSON, Raindrop JSON or Pocket API `list` dumps.
+    Json,
+    /// One URL per line; `#` starts a comment.
+    UrlList,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("could not read JSON export: {0}")]
+    Json(String),
+    #[error("CSV export has no url column")]
+    MissingUrlColumn,
+    #[error("no bookmarks found in the import")]
+    Empty,
+}
+
+// Column and JSON key aliases, most specific first.
+const URL_KEYS: &[&str] = &["url", "href", "link", "given_url", "resolved_url"];
+const TITLE_KEYS: &[&str] = &[
+    "title",
+    "description",
+    "resolved_title",
+    "given_title",
+    "name",
+];
+const NOTES_KEYS: &[&str] = &["note", "notes", "extended", "excerpt", "selection"];
+const TAG_KEYS: &[&str] = &["tags", "tag"];
+const TIME_KEYS: &[&str] = &[
+    "time",
+    "time_added",
+    "add_date",
+    "created",
+    "timestamp",
+    "date",
+];
+
+pub fn detect_format(content: &str) -> ImportFormat {
+    let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        return ImportFormat::Json;
+    }
+    let lowered = trimmed
+        .chars()
+        .take(4096)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if lowered.starts_with('<') && lowered.contains("<a ") {
+        return ImportFormat::Netscape;
+    }
+    let header = lowered.lines().next().unwrap_or("");
+    if header.contains(',')
+        && parse_csv(header)
+            .first()
+            .is_some_and(|columns| columns.iter().any(|c| URL_KEYS.contains(&c.trim())))
+    {
+        return ImportFormat::Csv;
+    }
+    ImportFormat::UrlList
+}
+
+/// Parses an export into create payloads. Fields are taken as found; URL and
+/// tag normalization, validation and dedupe happen when the import runs.
+pub fn parse_import(
+    content: &str,
+    format: ImportFormat,
+) -> Result<Vec<BookmarkPayload>, ImportError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let payloads = match format {
+        ImportFormat::Netscape => parse_netscape(content),
+        ImportFormat::Csv => parse_csv_export(content)?,
+        ImportFormat::Json => parse_json_export(content)?,
+        ImportFormat::UrlList => parse_url_list(content),
+    };
+
+    if payloads.is_empty() {
+        return Err(ImportError::Empty);
+    }
+    Ok(payloads)
+}
+
+fn parse_netscape(content: &str) -> Vec<BookmarkPayload> {
+    static ANCHOR: OnceLock<Regex> = OnceLock::new();
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let anchor = ANCHOR.get_or_init(|| Regex::new(r"(?is)<a\s([^>]*)>(.*?)</a>").unwrap());
+    let attribute = ATTRIBUTE
+        .get_or_init(|| Regex::new(r#"(?is)([a-z_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+    anchor
+        .captures_iter(content)
+        .filter_map(|captures| {
+            let mut record = attribute
+                .captures_iter(&captures[1])
+                .map(|attr| {
+                    let value = attr
+                        .get(2)
+                        .or_else(|| attr.get(3))
+                        .map_or("", |m| m.as_str());
+                    (attr[1].to_ascii_lowercase(), decode_entities(value))
+                })
+                .collect::<HashMap<_, _>>();
+            record.insert(
+                "title".to_string(),
+                decode_entities(&strip_tags(&captures[2])),
+            );
+
+            // `<DD>` right after the link holds the description.
+            let rest = content[captures.get(0)?.end()..].trim_start();
+            if rest
+                .get(..4)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("<dd>"))
+            {
+                let notes = rest[4..].split('<').next().unwrap_or("");
+                record.insert("notes".to_string(), decode_entities(notes.trim()));
+            }
+
+            payload_from_record(&record)
+        })
+        .collect()
+}
+
+fn parse_csv_export(content: &str) -> Result<Vec<BookmarkPayload>, ImportError> {
+    let mut rows = parse_csv(content).into_iter();
+    let header = rows
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    if !header
+        .iter()
+        .any(|column| URL_KEYS.contains(&column.as_str()))
+    {
+        return Err(ImportError::MissingUrlColumn);
+    }
+
+    Ok(rows
+        .filter_map(|row| {
+            let record = header
+                .iter()
+                .cloned()
+                .zip(row)
+                .collect::<HashMap<String, String>>();
+            payload_from_record(&record)
+        })
+        .collect())
+}
+
+fn parse_json_export(content: &str) -> Result<Vec<BookmarkPayload>, ImportError> {
+    let value =
+        serde_json::from_str::<Value>(content).map_err(|e| ImportError::Json(e.to_string()))?;
+
+    let items = match &value {
+        Value::Array(items) => items.clone(),
+        Value::Object(object) => ["items", "bookmarks", "posts", "list"]
+            .iter()
+            .find_map(|key| match object.get(*key) {
+                Some(Value::Array(items)) => Some(items.clone()),
+                // Pocket keys its `list` by item id.
+                Some(Value::Object(map)) => Some(map.values().cloned().collect()),
+                _ => None,
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(Value::as_object)
+        .filter_map(|object| {
+            let record = object
+                .iter()
+                .map(|(key, value)| (key.to_ascii_lowercase(), json_field(value)))
+                .collect::<HashMap<_, _>>();
+            payload_from_record(&record)
+        })
+        .collect())
+}
+
+fn parse_url_list(content: &str) -> Vec<BookmarkPayload> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let url = line.split_whitespace().next()?;
+            let record = HashMap::from([("url".to_string(), url.to_string())]);
+            payload_from_record(&record)
+        })
+        .collect()
+}
+
+fn json_field(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Array(items) => items.iter().map(json_field).collect::<Vec<_>>().join(","),
+        // Pocket stores tags as `{ "name": { ... } }`.
+        Value::Object(map) => map.keys().cloned().collect::<Vec<_>>().join(","),
+        Value::Null => String::new(),
+    }
+}
+
+/// Builds a payload from one exported record keyed by lowercase field name.
+fn payload_from_record(record: &HashMap<String, String>) -> Option<BookmarkPayload> {
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| record.get(*key))
+            .map(|value| value.trim())
+            .find(|value| !value.is_empty())
+            .map(ToString::to_string)
+    };
+
+    let url = field(URL_KEYS)?;
+    let flag = |key: &str| record.get(key).and_then(|value| parse_flag(value));
+
+    let read_later = flag("toread")
+        .or_else(|| flag("unread"))
+        .or_else(|| {
+            // Pocket `status` is 0 (unread) / 1 (archived) or "unread" / "archive";
+            // Instapaper files unread items in the "Unread" folder.
+            let status = record.get("status").or_else(|| record.get("folder"))?;
+            match status.trim().to_ascii_lowercase().as_str() {
+                "0" | "unread" => Some(true),
+                "1" | "archive" | "archived" => Some(false),
+                _ => None,
+            }
+        })
+        .unwrap_or(false);
+    let private = flag("private")
+        .or_else(|| flag("shared").map(|shared| !shared))
+        .unwrap_or(false);
+
+    Some(BookmarkPayload {
+        url,
+        title: field(TITLE_KEYS).unwrap_or_default(),
+        notes: field(NOTES_KEYS).unwrap_or_default(),
+        tags: field(TAG_KEYS)
+            .map(|raw| split_tags(&raw))
+            .unwrap_or_default(),
+        private,
+        read_later,
+        intent: SubmitIntent::Create,
+        created_at: field(TIME_KEYS).and_then(|raw| parse_time(&raw)),
+        base: None,
+    })
+}
+
+/// Exports separate tags with `|`, commas or spaces. Spaces inside a single
+/// tag become `-`, since Pinboard tags cannot contain them.
+fn split_tags(raw: &str) -> Vec<Tag> {
+    let separator = if raw.contains('|') {
+        Some('|')
+    } else if raw.contains(',') {
+        Some(',')
+    } else {
+        None
+    };
+
+    let parts: Vec<&str> = match separator {
+        Some(separator) => raw.split(separator).collect(),
+        None => raw.split_whitespace().collect(),
+    };
+    parts
+        .into_iter()
+        .filter_map(|tag| Tag::parse(&tag.split_whitespace().collect::<Vec<_>>().join("-")))
+        .collect()
+}
+
+fn parse_flag(raw: &str) -> Option<bool> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "1" | "yes" | "true" => Some(true),
+        "0" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Unix seconds (or milliseconds), RFC 3339 or `YYYY-MM-DD HH:MM:SS` (UTC)
+/// to RFC 3339.
+fn parse_time(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let parsed = if let Ok(number) = raw.parse::<i64>() {
+        let secs = if number > 100_000_000_000 {
+            number / 1000
+        } else {
+            number
+        };
+        chrono::DateTime::from_timestamp(secs, 0)?
+    } else if let Ok(time) = chrono::DateTime::parse_from_rfc3339(raw) {
+        time.with_timezone(&chrono::Utc)
+    } else {
+        chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S")
+            .ok()?
+            .and_utc()
+    };
+    (parsed.timestamp() > 0).then(|| parsed.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+/// Minimal RFC 4180 reader: quoted fields may hold commas, newlines and
+/// doubled quotes.
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|value| !value.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            ch => field.push(ch),
+        }
+    }
+
+    row.push(field);
+    if row.iter().any(|value| !value.is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+fn strip_tags(html: &str) -> String {
+    static TAG: OnceLock<Regex> = OnceLock::new();
+    TAG.get_or_init(|| Regex::new(r"<[^>]*>").unwrap())
+        .replace_all(html, "")
+        .trim()
+        .to_string()
+}
+
+fn decode_entities(text: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+    ENTITY
+        .get_or_init(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap())
+        .replace_all(text, |captures: &regex::Captures<'_>| {
+            let entity = &captures[1];
+            let decoded = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_format, parse_import, ImportFormat};
+
+    #[test]
+    fn parses_netscape_html_with_descriptions() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<DL><p>
+<DT><H3>Folder</H3>
+<DL><p>
+<DT><A HREF="https://example.com/a?x=1&amp;y=2" ADD_DATE="1700000000" PRIVATE="1" TOREAD="1" TAGS="rust,async">A &amp; B</A>
+<DD>Notes about &lt;A&gt;
+<DT><A HREF="https://example.org/">Plain</A>
+</DL><p>
+</DL>"#;
+        assert_eq!(detect_format(html), ImportFormat::Netscape);
+
+        let payloads = parse_import(html, ImportFormat::Netscape).unwrap();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].url, "https://example.com/a?x=1&y=2");
+        assert_eq!(payloads[0].title, "A & B");
+        assert_eq!(payloads[0].notes, "Notes about <A>");
+        assert_eq!(payloads[0].tags, vec!["rust", "async"]);
+        assert!(payloads[0].private && payloads[0].read_later);
+        assert_eq!(
+            payloads[0].created_at.as_deref(),
+            Some("2023-11-14T22:13:20Z")
+        );
+        assert!(payloads[1].notes.is_empty() && !payloads[1].private);
+    }
+
+    #[test]
+    fn non_ascii_text_after_a_link_is_not_a_description() {
+        let html = r#"<DT><A HREF="https://example.com/">Café</A>日本語のテキスト
+<DT><A HREF="https://example.org/">Ünïcode</A>é"#;
+        let payloads = parse_import(html, ImportFormat::Netscape).unwrap();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[0].title, "Café");
+        assert!(payloads[0].notes.is_empty() && payloads[1].notes.is_empty());
+    }
+
+    #[test]
+    fn parses_service_csv_exports() {
+        let pocket = "title,url,time_added,tags,status\n\
+                      \"Hello, world\",https://a.example/,1700000000,rust|web dev,unread\n\
+                      Done,https://b.example/,1700000001,,archive\n";
+        assert_eq!(detect_format(pocket), ImportFormat::Csv);
+        let payloads = parse_import(pocket, ImportFormat::Csv).unwrap();
+        assert_eq!(payloads[0].title, "Hello, world");
+        assert_eq!(payloads[0].tags, vec!["rust", "web-dev"]);
+        assert!(payloads[0].read_later && !payloads[1].read_later);
+
+        let instapaper = "URL,Title,Selection,Folder,Timestamp\r\n\
+                          https://c.example/,\"Multi\nline\",quote,Unread,1700000002\r\n";
+        let payloads = parse_import(instapaper, ImportFormat::Csv).unwrap();
+        assert_eq!(payloads[0].title, "Multi\nline");
+        assert_eq!(payloads[0].notes, "quote");
+        assert!(payloads[0].read_later);
+
+        assert!(parse_import("title,notes\nx,y\n", ImportFormat::Csv).is_err());
+    }
+
+    #[test]
+    fn parses_json_exports_and_url_lists() {
+        let pinboard = r#"[{"href":"https://a.example/","description":"A","extended":"n",
+            "tags":"x y","time":"2020-01-02T03:04:05Z","shared":"no","toread":"yes"}]"#;
+        assert_eq!(detect_format(pinboard), ImportFormat::Json);
+        let payloads = parse_import(pinboard, ImportFormat::Json).unwrap();
+        assert_eq!(payloads[0].tags, vec!["x", "y"]);
+        assert!(payloads[0].private && payloads[0].read_later);
+        assert_eq!(
+            payloads[0].created_at.as_deref(),
+            Some("2020-01-02T03:04:05Z")
+        );
+
+        let raindrop = r#"{"items":[{"link":"https://b.example/","title":"B",
+            "tags":["one","two"],"created":"2021-05-06T07:08:09.000Z"}]}"#;
+        let payloads = parse_import(raindrop, ImportFormat::Json).unwrap();
+        assert_eq!(payloads[0].url, "https://b.example/");
+        assert_eq!(payloads[0].tags, vec!["one", "two"]);
+
+        let list = "# reading list\nhttps://c.example/ trailing words\n\nd.example\n";
+        assert_eq!(detect_format(list), ImportFormat::UrlList);
+        let payloads = parse_import(list, ImportFormat::UrlList).unwrap();
+        assert_eq!(payloads.len(), 2);
+        assert_eq!(payloads[1].url, "d.example");
+    }
+}
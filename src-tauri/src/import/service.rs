@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::domain::bookmark::{normalize_url, BookmarkPayload};
+use crate::domain::tags::{normalize_tags, tag_strings, TagNormalization};
+use crate::domain::validation::{validate_payload, MAX_TITLE_CHARS};
+use crate::queue::store::QueueOperation;
+use crate::security::accounts::account_settings;
+use crate::settings::store::TAG_NORMALIZATION_KEY;
+use crate::AppState;
+
+// Entries handed to the queue per transaction; progress is saved after each.
+const IMPORT_BATCH_SIZE: usize = 50;
+
+/// Starts `run_pending_imports` off the async runtime; a run already in
+/// progress picks up new jobs itself.
+pub fn spawn_pending_imports<R: Runtime>(app: AppHandle<R>, state: Arc<AppState>) {
+    tauri::async_runtime::spawn_blocking(move || run_pending_imports(&app, &state));
+}
+
+/// Runs every unfinished import job in order, one at a time. Entries are
+/// added to the offline queue, so the worker's write pacing applies.
+/// Emits `import:progress` after each batch and `import:finished` per job.
+pub fn run_pending_imports<R: Runtime>(app: &AppHandle<R>, state: &Arc<AppState>) {
+    loop {
+        if !state.imports.begin_run() {
+            return;
+        }
+
+        let mut attempted = HashSet::new();
+        while let Some(id) = next_unfinished(state) {
+            if !attempted.insert(id) {
+                break;
+            }
+            if let Err(err) = run_job(app, state, id) {
+                let _ = state.imports.finish(id, Some(&err));
+            }
+            if let Ok(Some(job)) = state.imports.get(id) {
+                let _ = app.emit("import:finished", job);
+            }
+        }
+        state.imports.end_run();
+
+        // A job created while the last one was finishing would otherwise
+        // wait for the next start.
+        match next_unfinished(state) {
+            Some(id) if !attempted.contains(&id) => continue,
+            _ => return,
+        }
+    }
+}
+
+fn next_unfinished(state: &AppState) -> Option<i64> {
+    state.imports.unfinished().ok()?.first().copied()
+}
+
+fn run_job<R: Runtime>(app: &AppHandle<R>, state: &AppState, id: i64) -> Result<(), String> {
+    let job = state
+        .imports
+        .get(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("import job {id} not found"))?;
+    let payloads = state.imports.payloads(id).map_err(|e| e.to_string())?;
+    let normalization = state
+        .settings_store
+        .get::<TagNormalization>(TAG_NORMALIZATION_KEY)
+        .map_err(|e| e.to_string())?;
+    // The local collection is the active account's; an import for another
+    // account only skips duplicates within the file.
+    let check_library = account_settings(state)?.active == job.account;
+
+    let processed = (job.processed.max(0) as usize).min(payloads.len());
+    let mut counts = job.counts;
+    // Entries before the resume point were already queued or skipped.
+    let mut seen = payloads[..processed]
+        .iter()
+        .filter_map(|payload| normalize_url(&payload.url))
+        .collect::<HashSet<_>>();
+
+    for (batch_index, batch) in payloads[processed..].chunks(IMPORT_BATCH_SIZE).enumerate() {
+        let mut operations = Vec::new();
+        for payload in batch {
+            let Some(clean) = prepare_payload(payload, &normalization) else {
+                counts.invalid += 1;
+                continue;
+            };
+            let known = check_library
+                && state
+                    .library
+                    .get(&clean.url)
+                    .map_err(|e| e.to_string())?
+                    .is_some();
+            if known || !seen.insert(clean.url.clone()) {
+                counts.duplicates += 1;
+                continue;
+            }
+            operations.push(QueueOperation::AddBookmark { payload: clean });
+        }
+
+        state
+            .queue_store
+            .enqueue_batch(&job.account, &operations)
+            .map_err(|e| e.to_string())?;
+        counts.queued += operations.len() as i64;
+
+        let done = processed + batch_index * IMPORT_BATCH_SIZE + batch.len();
+        state
+            .imports
+            .record_progress(id, done as i64, counts)
+            .map_err(|e| e.to_string())?;
+        if let Ok(Some(job)) = state.imports.get(id) {
+            let _ = app.emit("import:progress", job);
+        }
+    }
+
+    state.imports.finish(id, None).map_err(|e| e.to_string())?;
+    if let Ok(stats) = state.queue_store.stats() {
+        let _ = app.emit("queue:stats_updated", stats);
+    }
+    Ok(())
+}
+
+/// Normalizes an imported entry the way a manual capture would be, or
+/// returns `None` if it still would not pass validation. Missing titles fall
+/// back to the URL and long ones are cut to Pinboard's limit.
+fn prepare_payload(
+    payload: &BookmarkPayload,
+    normalization: &TagNormalization,
+) -> Option<BookmarkPayload> {
+    let url = normalize_url(&payload.url)?;
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return None;
+    }
+
+    let title = if payload.title.trim().is_empty() {
+        url.clone()
+    } else {
+        payload.title.trim().to_string()
+    };
+    let clean = BookmarkPayload {
+        title: title.chars().take(MAX_TITLE_CHARS).collect(),
+        tags: normalize_tags(&tag_strings(&payload.tags), normalization),
+        url,
+        ..payload.clone()
+    };
+
+    validate_payload(&clean).is_empty().then_some(clean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prepare_payload, run_pending_imports};
+    use crate::api::mock_server::{app_state, MockPinboard};
+    use crate::domain::bookmark::ExistingBookmark;
+    use crate::domain::tags::TagNormalization;
+    use crate::import::formats::{parse_import, ImportFormat};
+
+    #[test]
+    fn prepare_fills_titles_and_drops_unusable_entries() {
+        let payloads = parse_import(
+            "example.com/page\njavascript:void(0)\nhttps://a.example/#x\n",
+            ImportFormat::UrlList,
+        )
+        .unwrap();
+        let normalization = TagNormalization::default();
+
+        let first = prepare_payload(&payloads[0], &normalization).unwrap();
+        assert_eq!(first.url, "https://example.com/page");
+        assert_eq!(first.title, "https://example.com/page");
+        assert!(prepare_payload(&payloads[1], &normalization).is_none());
+        assert!(prepare_payload(&payloads[2], &normalization).is_some());
+    }
+
+    #[tokio::test]
+    async fn only_the_active_accounts_import_skips_known_bookmarks() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "import-accounts");
+        let app = tauri::test::mock_app();
+        state
+            .library
+            .upsert(&ExistingBookmark {
+                url: "https://a.example/".to_string(),
+                title: "Known".to_string(),
+                notes: String::new(),
+                tags: Vec::new(),
+                private: false,
+                read_later: false,
+                time: "2024-01-01T00:00:00Z".to_string(),
+                meta: String::new(),
+            })
+            .unwrap();
+
+        let payloads = parse_import(
+            "https://a.example/\nhttps://b.example/\n",
+            ImportFormat::UrlList,
+        )
+        .unwrap();
+        let active = state
+            .imports
+            .create("default", "links.txt", ImportFormat::UrlList, &payloads)
+            .unwrap();
+        let other = state
+            .imports
+            .create("work", "links.txt", ImportFormat::UrlList, &payloads)
+            .unwrap();
+        run_pending_imports(app.handle(), &state);
+
+        let active = state.imports.get(active.id).unwrap().unwrap();
+        assert_eq!((active.counts.queued, active.counts.duplicates), (1, 1));
+        let other = state.imports.get(other.id).unwrap().unwrap();
+        assert_eq!((other.counts.queued, other.counts.duplicates), (2, 0));
+    }
+}
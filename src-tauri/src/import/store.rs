@@ -0,0 +1,267 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use crate::domain::bookmark::BookmarkPayload;
+use crate::import::formats::ImportFormat;
+use crate::infra::db::{database_path, now_unix, open_db};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportStoreError {
+    #[error("db error: {0}")]
+    Db(String),
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCounts {
+    pub queued: i64,
+    pub duplicates: i64,
+    pub invalid: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportJob {
+    pub id: i64,
+    /// The account entries are queued for.
+    pub account: String,
+    pub source: String,
+    pub format: ImportFormat,
+    /// "running", "done" or "failed".
+    pub status: String,
+    pub total: i64,
+    /// Parsed entries handled so far; a resumed job continues from here.
+    pub processed: i64,
+    pub counts: ImportCounts,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Import jobs with their parsed payloads, so an import interrupted by a
+/// restart picks up where it stopped.
+pub struct ImportStore {
+    db_path: PathBuf,
+    running: AtomicBool,
+}
+
+impl ImportStore {
+    pub fn new(custom_path: &str) -> Result<Self, ImportStoreError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        Ok(Self {
+            db_path,
+            running: AtomicBool::new(false),
+        })
+    }
+
+    pub fn create(
+        &self,
+        account: &str,
+        source: &str,
+        format: ImportFormat,
+        payloads: &[BookmarkPayload],
+    ) -> Result<ImportJob, ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let format_json =
+            serde_json::to_string(&format).map_err(|e| ImportStoreError::Serde(e.to_string()))?;
+        let payloads_json =
+            serde_json::to_string(payloads).map_err(|e| ImportStoreError::Serde(e.to_string()))?;
+        let now = now_unix();
+        conn.execute(
+            "INSERT INTO import_jobs(account, source, format, status, payloads_json, total, created_at, updated_at)
+             VALUES(?1, ?2, ?3, 'running', ?4, ?5, ?6, ?6)",
+            params![
+                account,
+                source,
+                format_json,
+                payloads_json,
+                payloads.len() as i64,
+                now
+            ],
+        )
+        .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+
+        let id = conn.last_insert_rowid();
+        self.get(id)?
+            .ok_or_else(|| ImportStoreError::Db(format!("import job {id} vanished")))
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<ImportJob>, ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        conn.query_row(
+            &format!("SELECT {JOB_COLUMNS} FROM import_jobs WHERE id = ?1"),
+            params![id],
+            map_job,
+        )
+        .optional()
+        .map_err(|e| ImportStoreError::Db(e.to_string()))
+    }
+
+    pub fn list(&self, limit: usize) -> Result<Vec<ImportJob>, ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {JOB_COLUMNS} FROM import_jobs ORDER BY id DESC LIMIT ?1"
+            ))
+            .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![limit as i64], map_job)
+            .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ImportStoreError::Db(e.to_string()))
+    }
+
+    /// Ids of jobs that were still running when the app last stopped.
+    pub fn unfinished(&self) -> Result<Vec<i64>, ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let mut stmt = conn
+            .prepare("SELECT id FROM import_jobs WHERE status = 'running' ORDER BY id ASC")
+            .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, i64>(0))
+            .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ImportStoreError::Db(e.to_string()))
+    }
+
+    pub fn payloads(&self, id: i64) -> Result<Vec<BookmarkPayload>, ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let json = conn
+            .query_row(
+                "SELECT payloads_json FROM import_jobs WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        serde_json::from_str(&json).map_err(|e| ImportStoreError::Serde(e.to_string()))
+    }
+
+    pub fn record_progress(
+        &self,
+        id: i64,
+        processed: i64,
+        counts: ImportCounts,
+    ) -> Result<(), ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        conn.execute(
+            "UPDATE import_jobs
+             SET processed = ?1, queued = ?2, duplicates = ?3, invalid = ?4, updated_at = ?5
+             WHERE id = ?6",
+            params![
+                processed,
+                counts.queued,
+                counts.duplicates,
+                counts.invalid,
+                now_unix(),
+                id
+            ],
+        )
+        .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Closes a job. Payloads are dropped once they can no longer be resumed.
+    pub fn finish(&self, id: i64, error: Option<&str>) -> Result<(), ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        let status = if error.is_some() { "failed" } else { "done" };
+        conn.execute(
+            "UPDATE import_jobs
+             SET status = ?1, error = ?2, payloads_json = CASE WHEN ?2 IS NULL THEN '[]' ELSE payloads_json END,
+                 updated_at = ?3
+             WHERE id = ?4",
+            params![status, error, now_unix(), id],
+        )
+        .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Marks a failed job as running again so it can be resumed.
+    pub fn reopen(&self, id: i64) -> Result<(), ImportStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        conn.execute(
+            "UPDATE import_jobs SET status = 'running', error = NULL, updated_at = ?1
+             WHERE id = ?2 AND status = 'failed'",
+            params![now_unix(), id],
+        )
+        .map_err(|e| ImportStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn begin_run(&self) -> bool {
+        !self.running.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn end_run(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+const JOB_COLUMNS: &str = "id, source, format, status, total, processed, queued, duplicates, \
+                           invalid, error, created_at, updated_at, account";
+
+fn map_job(row: &Row<'_>) -> rusqlite::Result<ImportJob> {
+    let format_json: String = row.get(2)?;
+    Ok(ImportJob {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        format: serde_json::from_str(&format_json).unwrap_or(ImportFormat::UrlList),
+        status: row.get(3)?,
+        total: row.get(4)?,
+        processed: row.get(5)?,
+        counts: ImportCounts {
+            queued: row.get(6)?,
+            duplicates: row.get(7)?,
+            invalid: row.get(8)?,
+        },
+        error: row.get(9)?,
+        created_at: row.get(10)?,
+        updated_at: row.get(11)?,
+        account: row.get(12)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImportCounts, ImportStore};
+    use crate::import::formats::{parse_import, ImportFormat};
+    use crate::infra::db::TempDb;
+
+    #[test]
+    fn jobs_keep_payloads_until_finished() {
+        let db = TempDb::new("import");
+        let store = ImportStore::new(db.path().to_str().unwrap()).unwrap();
+        let payloads = parse_import(
+            "https://a.example/\nhttps://b.example/\n",
+            ImportFormat::UrlList,
+        )
+        .unwrap();
+
+        let job = store
+            .create("default", "urls.txt", ImportFormat::UrlList, &payloads)
+            .unwrap();
+        assert_eq!(job.total, 2);
+        assert_eq!(store.unfinished().unwrap(), vec![job.id]);
+
+        let counts = ImportCounts {
+            queued: 1,
+            duplicates: 0,
+            invalid: 0,
+        };
+        store.record_progress(job.id, 1, counts).unwrap();
+        let resumed = store.get(job.id).unwrap().unwrap();
+        assert_eq!(resumed.processed, 1);
+        assert_eq!(resumed.counts, counts);
+        assert_eq!(store.payloads(job.id).unwrap().len(), 2);
+
+        store.finish(job.id, None).unwrap();
+        assert!(store.unfinished().unwrap().is_empty());
+        assert!(store.payloads(job.id).unwrap().is_empty());
+        assert_eq!(store.list(10).unwrap()[0].status, "done");
+    }
+}
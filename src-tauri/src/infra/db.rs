@@ -1,4 +1,7 @@
 use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use rusqlite::Connection;
 
@@ -24,3 +27,40 @@ pub fn open_db(path: &PathBuf) -> rusqlite::Result<Connection> {
     run_migrations(&conn)?;
     Ok(conn)
 }
+
+pub fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// A database path in the temp dir that is removed again when dropped. Each
+/// guard gets its own file, so tests running in parallel never share one.
+#[cfg(test)]
+pub struct TempDb {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl TempDb {
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        let id = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("ommapin-{name}-{}-{id}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Self { path }
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
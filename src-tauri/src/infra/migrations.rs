@@ -16,8 +16,128 @@ pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
 
         CREATE INDEX IF NOT EXISTS idx_queue_items_due
         ON queue_items(status, next_attempt_at);
+
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS tag_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            host TEXT NOT NULL,
+            tags_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_tag_history_created
+        ON tag_history(created_at);
+
+        CREATE TABLE IF NOT EXISTS user_tags (
+            tag TEXT PRIMARY KEY,
+            count INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_state (
+            name TEXT PRIMARY KEY,
+            fetched_at INTEGER NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS bookmarks (
+            id INTEGER PRIMARY KEY,
+            url TEXT NOT NULL UNIQUE,
+            host TEXT NOT NULL,
+            title TEXT NOT NULL,
+            notes TEXT NOT NULL,
+            tags TEXT NOT NULL,
+            private INTEGER NOT NULL,
+            read_later INTEGER NOT NULL,
+            time TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_bookmarks_host
+        ON bookmarks(host);
+
+        CREATE TABLE IF NOT EXISTS undo_entries (
+            url TEXT PRIMARY KEY,
+            previous_json TEXT,
+            captured_at INTEGER NOT NULL,
+            submitted_at INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS import_jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source TEXT NOT NULL,
+            format TEXT NOT NULL,
+            status TEXT NOT NULL,
+            payloads_json TEXT NOT NULL,
+            total INTEGER NOT NULL,
+            processed INTEGER NOT NULL DEFAULT 0,
+            queued INTEGER NOT NULL DEFAULT 0,
+            duplicates INTEGER NOT NULL DEFAULT 0,
+            invalid INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS bookmarks_fts USING fts5(
+            url,
+            title,
+            notes,
+            tags,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
         ",
     )?;
 
+    // Columns added after their table was first created.
+    add_column_if_missing(
+        conn,
+        "queue_items",
+        "account",
+        "TEXT NOT NULL DEFAULT 'default'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "import_jobs",
+        "account",
+        "TEXT NOT NULL DEFAULT 'default'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "tag_history",
+        "account",
+        "TEXT NOT NULL DEFAULT 'default'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "undo_entries",
+        "account",
+        "TEXT NOT NULL DEFAULT 'default'",
+    )?;
+
+    Ok(())
+}
+
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> rusqlite::Result<()> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({table})"))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+        ))?;
+    }
     Ok(())
 }
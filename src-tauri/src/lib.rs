@@ -1,36 +1,102 @@
 mod api;
 mod app;
+mod backup;
 mod dedupe;
 mod domain;
+mod export;
+mod import;
 mod infra;
+mod library;
 mod queue;
 mod security;
+mod settings;
+mod tagging;
+mod undo;
 
 use std::sync::Arc;
 
-use api::pinboard::PinboardClient;
+use api::pinboard::PinboardClients;
 use app::commands::{
-    check_duplicate, clear_token, fetch_tag_suggestions, fetch_url_title, fetch_user_tags,
-    get_omarchy_theme, init_session, queue_list, queue_retry_now, save_token, submit_bookmark,
+    check_duplicate, clear_token, create_backup, delete_bookmark, delete_user_tag,
+    evaluate_domain_rules, expand_tag_bundles, export_bookmarks, fetch_tag_suggestions,
+    fetch_url_title, fetch_user_tags, get_credential_settings, get_domain_rules, get_omarchy_theme,
+    get_tag_bundles, get_tag_normalization, get_undo_status, import_bookmarks, import_jobs,
+    init_session, list_accounts, mark_bookmark_read, merge_user_tags, preview_tag_normalization,
+    queue_conflicts, queue_list, queue_retry_now, rank_tag_suggestions, read_later_inbox,
+    recent_bookmarks, rename_user_tag, resolve_queue_conflict, restore_backup, resume_import,
+    save_account_backend, save_credential_settings, save_domain_rules, save_tag_bundles,
+    save_tag_normalization, save_token, search_bookmarks, set_active_account, submit_bookmark,
+    sync_bookmarks, undo_last_submission, unlock_credentials,
 };
+use backup::store::BackupStore;
+use import::store::ImportStore;
+use library::store::LibraryStore;
 use queue::store::QueueStore;
-use security::token_store::TokenStore;
+use security::credentials::CredentialStore;
+use security::file_store::EncryptedFileStore;
+use security::token_store::{CredentialControl, TokenStore};
+use settings::store::{SettingsStore, CREDENTIALS_KEY};
+use tagging::cache::TagCacheStore;
+use tagging::history::TagHistoryStore;
 use tauri::{Manager, WebviewWindowBuilder};
+use undo::store::UndoStore;
 
 pub struct AppState {
-    pub token_store: TokenStore,
-    pub pinboard: PinboardClient,
+    pub credentials: Arc<CredentialControl>,
+    pub token_store: Box<dyn CredentialStore>,
+    pub pinboard: PinboardClients,
     pub queue_store: QueueStore,
+    pub settings_store: SettingsStore,
+    pub tag_history: TagHistoryStore,
+    pub tag_cache: TagCacheStore,
+    pub library: LibraryStore,
+    pub undo: UndoStore,
+    pub imports: ImportStore,
+    pub backups: BackupStore,
+}
+
+impl AppState {
+    /// Opens every store on the database at `db_path` (the default location
+    /// when empty) and applies the saved credential settings to `credentials`.
+    pub fn new(
+        db_path: &str,
+        credentials: Arc<CredentialControl>,
+        token_store: Box<dyn CredentialStore>,
+    ) -> Result<Self, String> {
+        let settings_store = SettingsStore::new(db_path)
+            .map_err(|e| format!("failed to initialize settings store: {e}"))?;
+        credentials.configure(settings_store.get(CREDENTIALS_KEY).unwrap_or_default());
+
+        Ok(Self {
+            credentials,
+            token_store,
+            pinboard: PinboardClients::new(),
+            queue_store: QueueStore::new(db_path)
+                .map_err(|e| format!("failed to initialize queue store: {e}"))?,
+            settings_store,
+            tag_history: TagHistoryStore::new(db_path)
+                .map_err(|e| format!("failed to initialize tag history: {e}"))?,
+            tag_cache: TagCacheStore::new(db_path)
+                .map_err(|e| format!("failed to initialize tag cache: {e}"))?,
+            library: LibraryStore::new(db_path)
+                .map_err(|e| format!("failed to initialize bookmark library: {e}"))?,
+            undo: UndoStore::new(db_path)
+                .map_err(|e| format!("failed to initialize undo store: {e}"))?,
+            imports: ImportStore::new(db_path)
+                .map_err(|e| format!("failed to initialize import store: {e}"))?,
+            backups: BackupStore::new(db_path)
+                .map_err(|e| format!("failed to initialize backup store: {e}"))?,
+        })
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let queue_store = QueueStore::new("").expect("failed to initialize queue store");
-    let state = Arc::new(AppState {
-        token_store: TokenStore::new(),
-        pinboard: PinboardClient::new(),
-        queue_store,
-    });
+    let credentials = Arc::new(CredentialControl::new(EncryptedFileStore::default_path()));
+    let token_store = Box::new(TokenStore::new(credentials.clone()));
+    let state = Arc::new(
+        AppState::new("", credentials, token_store).expect("failed to initialize ommapin"),
+    );
 
     tauri::Builder::default()
         .manage(state)
@@ -51,6 +117,7 @@ pub fn run() {
         .setup(|app| {
             let app_handle = app.handle().clone();
             let state = app.state::<Arc<AppState>>().inner().clone();
+            import::service::spawn_pending_imports(app_handle.clone(), state.clone());
             tauri::async_runtime::spawn(async move {
                 queue::worker::run_background_worker(app_handle, state).await;
             });
@@ -60,6 +127,12 @@ pub fn run() {
             init_session,
             save_token,
             clear_token,
+            list_accounts,
+            set_active_account,
+            save_account_backend,
+            get_credential_settings,
+            save_credential_settings,
+            unlock_credentials,
             fetch_tag_suggestions,
             fetch_user_tags,
             fetch_url_title,
@@ -67,7 +140,36 @@ pub fn run() {
             submit_bookmark,
             queue_list,
             queue_retry_now,
+            queue_conflicts,
+            resolve_queue_conflict,
             get_omarchy_theme,
+            get_domain_rules,
+            save_domain_rules,
+            evaluate_domain_rules,
+            get_tag_normalization,
+            save_tag_normalization,
+            preview_tag_normalization,
+            rank_tag_suggestions,
+            rename_user_tag,
+            merge_user_tags,
+            delete_user_tag,
+            get_tag_bundles,
+            save_tag_bundles,
+            expand_tag_bundles,
+            search_bookmarks,
+            sync_bookmarks,
+            recent_bookmarks,
+            read_later_inbox,
+            mark_bookmark_read,
+            delete_bookmark,
+            get_undo_status,
+            undo_last_submission,
+            import_bookmarks,
+            import_jobs,
+            resume_import,
+            export_bookmarks,
+            create_backup,
+            restore_backup,
         ])
         .run(tauri::generate_context!())
         .expect("error while running ommapin");
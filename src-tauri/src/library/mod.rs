@@ -0,0 +1,3 @@
+pub mod query;
+pub mod service;
+pub mod store;
@@ -0,0 +1,192 @@
+use crate::domain::tags::Tag;
+
+/// A parsed search string. Supports Pinboard-style operators: `tag:rust`,
+/// `site:github.com`, `toread:yes|no`, `"quoted phrases"` and bare words.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub terms: Vec<String>,
+    pub phrases: Vec<String>,
+    pub tags: Vec<String>,
+    pub sites: Vec<String>,
+    pub read_later: Option<bool>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Self::default();
+
+        for token in split_tokens(input) {
+            match token {
+                Token::Phrase(phrase) => {
+                    if !phrase.trim().is_empty() {
+                        query.phrases.push(phrase);
+                    }
+                }
+                Token::Word(word) => query.push_word(word),
+            }
+        }
+
+        query
+    }
+
+    fn push_word(&mut self, word: String) {
+        if let Some((operator, value)) = word.split_once(':') {
+            let value = value.trim_matches('"');
+            match operator.to_ascii_lowercase().as_str() {
+                "tag" => {
+                    if let Some(tag) = Tag::parse(value) {
+                        self.tags.push(tag.to_string());
+                    }
+                    return;
+                }
+                "site" => {
+                    let host = value
+                        .trim_start_matches("https://")
+                        .trim_start_matches("http://")
+                        .trim_end_matches('/')
+                        .to_ascii_lowercase();
+                    if !host.is_empty() {
+                        self.sites.push(host);
+                    }
+                    return;
+                }
+                "toread" => {
+                    if let Some(flag) = parse_yes_no(value) {
+                        self.read_later = Some(flag);
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        self.terms.push(word);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+            && self.phrases.is_empty()
+            && self.tags.is_empty()
+            && self.sites.is_empty()
+            && self.read_later.is_none()
+    }
+
+    /// FTS5 `MATCH` expression for the free-text part, or `None` when the
+    /// query only filters. Every term is quoted so user input can never be
+    /// read as FTS syntax; bare words match as prefixes.
+    pub fn fts_expression(&self) -> Option<String> {
+        let parts = self
+            .phrases
+            .iter()
+            .map(|phrase| quote_fts(phrase))
+            .chain(
+                self.terms
+                    .iter()
+                    .map(|term| format!("{}*", quote_fts(term))),
+            )
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" AND "))
+        }
+    }
+}
+
+enum Token {
+    Word(String),
+    Phrase(String),
+}
+
+fn split_tokens(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for ch in input.chars() {
+        match ch {
+            '"' if in_quotes => {
+                in_quotes = false;
+                // `tag:"x"` stays one word; a standalone quote is a phrase.
+                if current.contains(':') && !current.contains(' ') {
+                    tokens.push(Token::Word(std::mem::take(&mut current)));
+                } else {
+                    tokens.push(Token::Phrase(std::mem::take(&mut current)));
+                }
+            }
+            '"' => {
+                in_quotes = true;
+                if !current.ends_with(':') && !current.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut current)));
+                }
+            }
+            ch if ch.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(Token::Word(std::mem::take(&mut current)));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+
+    if !current.is_empty() {
+        if in_quotes {
+            tokens.push(Token::Phrase(current));
+        } else {
+            tokens.push(Token::Word(current));
+        }
+    }
+
+    tokens
+}
+
+fn parse_yes_no(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" => Some(true),
+        "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn quote_fts(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchQuery;
+
+    #[test]
+    fn parses_operators_phrases_and_words() {
+        let query = SearchQuery::parse(
+            r#"tag:rust site:https://GitHub.com/ toread:yes "error handling" async"#,
+        );
+        assert_eq!(query.tags, vec!["rust"]);
+        assert_eq!(query.sites, vec!["github.com"]);
+        assert_eq!(query.read_later, Some(true));
+        assert_eq!(query.phrases, vec!["error handling"]);
+        assert_eq!(query.terms, vec!["async"]);
+        assert_eq!(
+            query.fts_expression().as_deref(),
+            Some(r#""error handling" AND "async"*"#)
+        );
+    }
+
+    #[test]
+    fn quoted_operator_values_and_unknown_operators() {
+        let query = SearchQuery::parse(r#"tag:".secret" toread:maybe c++ "unterminated"#);
+        assert_eq!(query.tags, vec![".secret"]);
+        assert_eq!(query.read_later, None);
+        assert_eq!(query.terms, vec!["toread:maybe", "c++"]);
+        assert_eq!(query.phrases, vec!["unterminated"]);
+    }
+
+    #[test]
+    fn filter_only_queries_have_no_fts_expression() {
+        let query = SearchQuery::parse("tag:rust");
+        assert!(query.fts_expression().is_none());
+        assert!(!query.is_empty());
+        assert!(SearchQuery::parse("  ").is_empty());
+    }
+}
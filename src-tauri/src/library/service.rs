@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::domain::bookmark::ExistingBookmark;
+use crate::infra::db::now_unix;
+use crate::library::query::SearchQuery;
+use crate::security::accounts::{active_credentials, Credentials};
+use crate::AppState;
+
+const LIBRARY_MAX_AGE_SECS: i64 = 15 * 60;
+// `posts/all` may only be called once every five minutes.
+const POSTS_ALL_MIN_INTERVAL_SECS: i64 = 5 * 60;
+
+/// Searches the local collection. A never-synced collection is downloaded
+/// inline; a stale one is refreshed in the background and announced with
+/// `library:synced`, so results always come back without waiting on Pinboard.
+pub async fn search_library(
+    app: &AppHandle,
+    state: &Arc<AppState>,
+    query: &SearchQuery,
+    limit: usize,
+) -> Result<Vec<ExistingBookmark>, String> {
+    let never_synced = state
+        .library
+        .fetched_at()
+        .map_err(|e| e.to_string())?
+        .is_none();
+
+    if never_synced {
+        let _ = sync_library(state).await;
+    } else if state
+        .library
+        .is_stale(LIBRARY_MAX_AGE_SECS)
+        .map_err(|e| e.to_string())?
+    {
+        let app = app.clone();
+        let state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Ok(count) = sync_library(&state).await {
+                let _ = app.emit("library:synced", count);
+            }
+        });
+    }
+
+    state
+        .library
+        .search(query, limit)
+        .map_err(|e| e.to_string())
+}
+
+/// Downloads the full collection and rebuilds the search index. Returns the
+/// number of bookmarks stored. Refused within five minutes of the previous
+/// `posts/all` call for the account.
+pub async fn sync_library(state: &Arc<AppState>) -> Result<usize, String> {
+    if !state.library.begin_sync() {
+        return Err("bookmark sync already running".to_string());
+    }
+
+    let result = fetch_and_store(state).await;
+    state.library.end_sync();
+    result
+}
+
+async fn fetch_and_store(state: &Arc<AppState>) -> Result<usize, String> {
+    let Credentials {
+        account,
+        token,
+        client,
+    } = active_credentials(state)?;
+
+    let last_call = state
+        .library
+        .last_full_fetch(&account)
+        .map_err(|e| e.to_string())?;
+    if let Some(last_call) = last_call {
+        let wait = POSTS_ALL_MIN_INTERVAL_SECS - (now_unix() - last_call);
+        if wait > 0 {
+            return Err(format!(
+                "Pinboard allows a full sync every five minutes; try again in {wait}s"
+            ));
+        }
+    }
+    // Counted before the call: a failed request still uses up the slot.
+    state
+        .library
+        .record_full_fetch(&account)
+        .map_err(|e| e.to_string())?;
+
+    let bookmarks = client
+        .get_all_bookmarks(&token)
+        .await
+        .map_err(|e| e.to_string())?;
+    state
+        .library
+        .replace_all(&bookmarks)
+        .map_err(|e| e.to_string())?;
+    Ok(bookmarks.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sync_library;
+    use crate::api::mock_server::{app_state, MockPinboard};
+
+    #[tokio::test]
+    async fn posts_all_is_called_at_most_every_five_minutes() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "library-sync");
+
+        assert_eq!(sync_library(&state).await, Ok(0));
+        let again = sync_library(&state).await.unwrap_err();
+        assert!(again.contains("every five minutes"), "{again}");
+        // Clearing the collection doesn't reset the limit.
+        state.library.clear().unwrap();
+        assert!(sync_library(&state).await.is_err());
+        assert_eq!(server.requests_to("posts/all").len(), 1);
+    }
+}
@@ -0,0 +1,396 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+
+use crate::domain::bookmark::{url_host, ExistingBookmark};
+use crate::infra::db::{database_path, now_unix, open_db};
+use crate::library::query::SearchQuery;
+
+const BOOKMARKS_SYNC_NAME: &str = "bookmarks";
+/// Followed by the account name; kept apart from `bookmarks` so clearing the
+/// collection doesn't reset the rate limit.
+const POSTS_ALL_CALL_PREFIX: &str = "posts_all:";
+
+// bm25 column weights, in `bookmarks_fts` column order: url, title, notes, tags.
+const RANK_WEIGHTS: &str = "2.0, 10.0, 1.0, 5.0";
+
+#[derive(Debug, thiserror::Error)]
+pub enum LibraryError {
+    #[error("db error: {0}")]
+    Db(String),
+}
+
+/// Local copy of the user's Pinboard collection (`posts/all`) with an FTS5
+/// index over url, title, notes and tags.
+pub struct LibraryStore {
+    db_path: PathBuf,
+    syncing: AtomicBool,
+}
+
+impl LibraryStore {
+    pub fn new(custom_path: &str) -> Result<Self, LibraryError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        Ok(Self {
+            db_path,
+            syncing: AtomicBool::new(false),
+        })
+    }
+
+    pub fn count(&self) -> Result<i64, LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.query_row("SELECT COUNT(*) FROM bookmarks", [], |row| row.get(0))
+            .map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    pub fn fetched_at(&self) -> Result<Option<i64>, LibraryError> {
+        self.sync_time(BOOKMARKS_SYNC_NAME)
+    }
+
+    /// When `posts/all` was last called for `account`, successfully or not.
+    pub fn last_full_fetch(&self, account: &str) -> Result<Option<i64>, LibraryError> {
+        self.sync_time(&format!("{POSTS_ALL_CALL_PREFIX}{account}"))
+    }
+
+    pub fn record_full_fetch(&self, account: &str) -> Result<(), LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO sync_state(name, fetched_at) VALUES(?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET fetched_at = excluded.fetched_at",
+            params![format!("{POSTS_ALL_CALL_PREFIX}{account}"), now_unix()],
+        )
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    fn sync_time(&self, name: &str) -> Result<Option<i64>, LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.query_row(
+            "SELECT fetched_at FROM sync_state WHERE name = ?1",
+            params![name],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    pub fn is_stale(&self, max_age_secs: i64) -> Result<bool, LibraryError> {
+        Ok(match self.fetched_at()? {
+            Some(fetched_at) => now_unix() - fetched_at >= max_age_secs,
+            None => true,
+        })
+    }
+
+    /// Replaces the local collection with a fresh `posts/all` result.
+    pub fn replace_all(&self, bookmarks: &[ExistingBookmark]) -> Result<(), LibraryError> {
+        let mut conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        let now = now_unix();
+        let tx = conn
+            .transaction()
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        tx.execute_batch("DELETE FROM bookmarks; DELETE FROM bookmarks_fts;")
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        for bookmark in bookmarks {
+            write_bookmark(&tx, bookmark, now)?;
+        }
+        tx.execute(
+            "INSERT INTO sync_state(name, fetched_at) VALUES(?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET fetched_at = excluded.fetched_at",
+            params![BOOKMARKS_SYNC_NAME, now],
+        )
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+        tx.commit().map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    /// Inserts or updates one bookmark, e.g. right after a local submit.
+    pub fn upsert(&self, bookmark: &ExistingBookmark) -> Result<(), LibraryError> {
+        let mut conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        write_bookmark(&tx, bookmark, now_unix())?;
+        tx.commit().map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    pub fn get(&self, url: &str) -> Result<Option<ExistingBookmark>, LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.query_row(
+            "SELECT url, title, notes, tags, private, read_later, time
+             FROM bookmarks WHERE url = ?1",
+            params![url],
+            bookmark_from_row,
+        )
+        .optional()
+        .map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    pub fn remove(&self, url: &str) -> Result<(), LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM bookmarks_fts WHERE rowid IN (SELECT id FROM bookmarks WHERE url = ?1)",
+            params![url],
+        )
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.execute("DELETE FROM bookmarks WHERE url = ?1", params![url])
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> Result<Vec<ExistingBookmark>, LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        let mut sql = String::from(
+            "SELECT bookmarks.url, bookmarks.title, bookmarks.notes, bookmarks.tags,
+                    bookmarks.private, bookmarks.read_later, bookmarks.time
+             FROM bookmarks",
+        );
+        let mut filters = Vec::new();
+        let mut values = Vec::<SqlValue>::new();
+
+        let fts = query.fts_expression();
+        if let Some(expression) = &fts {
+            sql.push_str(" JOIN bookmarks_fts ON bookmarks_fts.rowid = bookmarks.id");
+            filters.push("bookmarks_fts MATCH ?".to_string());
+            values.push(SqlValue::Text(expression.clone()));
+        }
+        for tag in &query.tags {
+            filters.push("(' ' || bookmarks.tags || ' ') LIKE ? ESCAPE '\\'".to_string());
+            values.push(SqlValue::Text(format!("% {} %", escape_like(tag))));
+        }
+        if !query.sites.is_empty() {
+            let clauses = query
+                .sites
+                .iter()
+                .map(|site| {
+                    values.push(SqlValue::Text(site.clone()));
+                    values.push(SqlValue::Text(format!("%.{}", escape_like(site))));
+                    "bookmarks.host = ? OR bookmarks.host LIKE ? ESCAPE '\\'"
+                })
+                .collect::<Vec<_>>();
+            filters.push(format!("({})", clauses.join(" OR ")));
+        }
+        if let Some(read_later) = query.read_later {
+            filters.push("bookmarks.read_later = ?".to_string());
+            values.push(SqlValue::Integer(i64::from(read_later)));
+        }
+
+        if !filters.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&filters.join(" AND "));
+        }
+        if fts.is_some() {
+            sql.push_str(&format!(
+                " ORDER BY bm25(bookmarks_fts, {RANK_WEIGHTS}), bookmarks.time DESC"
+            ));
+        } else {
+            sql.push_str(" ORDER BY bookmarks.time DESC");
+        }
+        sql.push_str(" LIMIT ?");
+        values.push(SqlValue::Integer(limit as i64));
+
+        let mut stmt = conn
+            .prepare(&sql)
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        let rows = stmt
+            .query_map(params_from_iter(values), bookmark_from_row)
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| LibraryError::Db(e.to_string()))
+    }
+
+    pub fn clear(&self) -> Result<(), LibraryError> {
+        let conn = open_db(&self.db_path).map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.execute_batch("DELETE FROM bookmarks; DELETE FROM bookmarks_fts;")
+            .map_err(|e| LibraryError::Db(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM sync_state WHERE name = ?1",
+            params![BOOKMARKS_SYNC_NAME],
+        )
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Returns `false` if a sync is already in flight.
+    pub fn begin_sync(&self) -> bool {
+        !self.syncing.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn end_sync(&self) {
+        self.syncing.store(false, Ordering::SeqCst);
+    }
+}
+
+fn bookmark_from_row(row: &Row<'_>) -> rusqlite::Result<ExistingBookmark> {
+    Ok(ExistingBookmark {
+        url: row.get(0)?,
+        title: row.get(1)?,
+        notes: row.get(2)?,
+        tags: row
+            .get::<_, String>(3)?
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect(),
+        private: row.get::<_, i64>(4)? != 0,
+        read_later: row.get::<_, i64>(5)? != 0,
+        time: row.get(6)?,
+        meta: String::new(),
+    })
+}
+
+fn write_bookmark(
+    conn: &Connection,
+    bookmark: &ExistingBookmark,
+    now: i64,
+) -> Result<(), LibraryError> {
+    let tags = bookmark.tags.join(" ");
+    let host = url_host(&bookmark.url).unwrap_or_default();
+    let id = conn
+        .query_row(
+            "INSERT INTO bookmarks(url, host, title, notes, tags, private, read_later, time, updated_at)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(url) DO UPDATE SET
+                host = excluded.host,
+                title = excluded.title,
+                notes = excluded.notes,
+                tags = excluded.tags,
+                private = excluded.private,
+                read_later = excluded.read_later,
+                time = excluded.time,
+                updated_at = excluded.updated_at
+             RETURNING id",
+            params![
+                bookmark.url,
+                host,
+                bookmark.title,
+                bookmark.notes,
+                tags,
+                i64::from(bookmark.private),
+                i64::from(bookmark.read_later),
+                bookmark.time,
+                now
+            ],
+            |row| row.get::<_, i64>(0),
+        )
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+
+    conn.execute("DELETE FROM bookmarks_fts WHERE rowid = ?1", params![id])
+        .map_err(|e| LibraryError::Db(e.to_string()))?;
+    conn.execute(
+        "INSERT INTO bookmarks_fts(rowid, url, title, notes, tags) VALUES(?1, ?2, ?3, ?4, ?5)",
+        params![id, bookmark.url, bookmark.title, bookmark.notes, tags],
+    )
+    .map_err(|e| LibraryError::Db(e.to_string()))?;
+    Ok(())
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LibraryStore;
+    use crate::domain::bookmark::ExistingBookmark;
+    use crate::infra::db::TempDb;
+    use crate::library::query::SearchQuery;
+
+    fn bookmark(url: &str, title: &str, tags: &[&str], read_later: bool) -> ExistingBookmark {
+        ExistingBookmark {
+            url: url.to_string(),
+            title: title.to_string(),
+            notes: String::new(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            private: false,
+            read_later,
+            time: format!("2024-01-0{}T00:00:00Z", title.len() % 9 + 1),
+            meta: String::new(),
+        }
+    }
+
+    fn search(store: &LibraryStore, query: &str) -> Vec<String> {
+        store
+            .search(&SearchQuery::parse(query), 10)
+            .unwrap()
+            .into_iter()
+            .map(|bookmark| bookmark.url)
+            .collect()
+    }
+
+    #[test]
+    fn searches_text_tags_sites_and_read_later() {
+        let db = TempDb::new("library");
+        let store = LibraryStore::new(db.path().to_str().unwrap()).unwrap();
+
+        store
+            .replace_all(&[
+                bookmark(
+                    "https://github.com/tokio-rs/tokio",
+                    "Tokio runtime",
+                    &["rust", "async"],
+                    false,
+                ),
+                bookmark("https://docs.rs/tokio", "tokio docs", &["rust"], true),
+                bookmark(
+                    "https://example.com/cooking",
+                    "Error handling in soups",
+                    &["rusty"],
+                    false,
+                ),
+                bookmark(
+                    "https://blog.rust-lang.org/",
+                    "Error handling in Rust",
+                    &[".rust"],
+                    false,
+                ),
+            ])
+            .unwrap();
+        store
+            .upsert(&bookmark(
+                "https://docs.rs/tokio",
+                "Tokio docs",
+                &["rust", "docs"],
+                true,
+            ))
+            .unwrap();
+
+        assert_eq!(store.count().unwrap(), 4);
+        assert_eq!(search(&store, "tok").len(), 2);
+        assert_eq!(
+            search(&store, "tokio site:github.com"),
+            vec!["https://github.com/tokio-rs/tokio"]
+        );
+        assert_eq!(
+            search(&store, "site:rust-lang.org"),
+            vec!["https://blog.rust-lang.org/"]
+        );
+        assert_eq!(
+            search(&store, "tag:rust toread:yes"),
+            vec!["https://docs.rs/tokio"]
+        );
+        assert_eq!(
+            search(&store, "tag:.rust"),
+            vec!["https://blog.rust-lang.org/"]
+        );
+        assert_eq!(search(&store, "tag:rust_").len(), 0);
+        assert_eq!(
+            search(&store, "\"error handling\" blog"),
+            vec!["https://blog.rust-lang.org/"]
+        );
+
+        store.remove("https://docs.rs/tokio").unwrap();
+        assert!(store.get("https://docs.rs/tokio").unwrap().is_none());
+        assert_eq!(
+            search(&store, "tokio"),
+            vec!["https://github.com/tokio-rs/tokio"]
+        );
+    }
+}
@@ -1,11 +1,12 @@
+use std::collections::BTreeSet;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Mutex, MutexGuard};
 
 use rusqlite::{params, Row};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::bookmark::BookmarkPayload;
-use crate::infra::db::{database_path, open_db};
+use crate::infra::db::{database_path, now_unix, open_db};
 
 const MIN_QUEUE_DELAY_SECS: i64 = 3;
 const MAX_RETRY_ATTEMPTS: i64 = 12;
@@ -23,13 +24,34 @@ pub enum QueueStoreError {
 pub struct QueueStats {
     pub pending: u64,
     pub failed: u64,
+    pub conflicts: u64,
+    /// The worker is holding items back until a valid token is saved for
+    /// one of these accounts.
+    pub paused: bool,
+    pub paused_accounts: Vec<String>,
+}
+
+/// A Pinboard write waiting to be replayed by the worker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum QueueOperation {
+    #[serde(rename_all = "camelCase")]
+    AddBookmark { payload: BookmarkPayload },
+    #[serde(rename_all = "camelCase")]
+    DeleteBookmark { url: String },
+    #[serde(rename_all = "camelCase")]
+    RenameTag { from: String, to: String },
+    #[serde(rename_all = "camelCase")]
+    DeleteTag { tag: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueueItem {
     pub id: i64,
-    pub payload: BookmarkPayload,
+    /// The account whose token replays this item.
+    pub account: String,
+    pub operation: QueueOperation,
     pub attempt_count: i64,
     pub next_attempt_at: i64,
     pub last_error: Option<String>,
@@ -37,18 +59,23 @@ pub struct QueueItem {
 
 pub struct QueueStore {
     db_path: PathBuf,
+    auth_paused: Mutex<BTreeSet<String>>,
 }
 
 impl QueueStore {
     pub fn new(custom_path: &str) -> Result<Self, QueueStoreError> {
         let db_path = database_path(custom_path);
         open_db(&db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
-        Ok(Self { db_path })
+        Ok(Self {
+            db_path,
+            auth_paused: Mutex::new(BTreeSet::new()),
+        })
     }
 
     pub fn enqueue(
         &self,
-        payload: &BookmarkPayload,
+        account: &str,
+        operation: &QueueOperation,
         err: &str,
         initial_delay_secs: i64,
     ) -> Result<(), QueueStoreError> {
@@ -56,31 +83,62 @@ impl QueueStore {
         let now = now_unix();
         let next_attempt = now + initial_delay_secs.max(MIN_QUEUE_DELAY_SECS);
         let payload_json =
-            serde_json::to_string(payload).map_err(|e| QueueStoreError::Serde(e.to_string()))?;
+            serde_json::to_string(operation).map_err(|e| QueueStoreError::Serde(e.to_string()))?;
         conn.execute(
-            "INSERT INTO queue_items(payload_json, status, attempt_count, next_attempt_at, last_error, created_at, updated_at)
-             VALUES(?1, 'pending', 0, ?2, ?3, ?2, ?2)",
-            params![payload_json, next_attempt, err],
+            "INSERT INTO queue_items(payload_json, account, status, attempt_count, next_attempt_at, last_error, created_at, updated_at)
+             VALUES(?1, ?2, 'pending', 0, ?3, ?4, ?3, ?3)",
+            params![payload_json, account, next_attempt, err],
         )
         .map_err(|e| QueueStoreError::Db(e.to_string()))?;
         Ok(())
     }
 
+    /// Adds many operations in one transaction, due immediately and spaced
+    /// only by the worker's pacing. Used by bulk imports.
+    pub fn enqueue_batch(
+        &self,
+        account: &str,
+        operations: &[QueueOperation],
+    ) -> Result<(), QueueStoreError> {
+        let mut conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        let now = now_unix();
+        let tx = conn
+            .transaction()
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        for operation in operations {
+            let payload_json = serde_json::to_string(operation)
+                .map_err(|e| QueueStoreError::Serde(e.to_string()))?;
+            tx.execute(
+                "INSERT INTO queue_items(payload_json, account, status, attempt_count, next_attempt_at, last_error, created_at, updated_at)
+                 VALUES(?1, ?2, 'pending', 0, ?3, NULL, ?3, ?3)",
+                params![payload_json, account, now],
+            )
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        }
+        tx.commit()
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Pending items that are due, skipping accounts paused for auth.
     pub fn due_items(&self, limit: usize) -> Result<Vec<QueueItem>, QueueStoreError> {
         let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
         let now = now_unix();
+        let paused = serde_json::to_string(&self.paused_accounts())
+            .map_err(|e| QueueStoreError::Serde(e.to_string()))?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, payload_json, attempt_count, next_attempt_at, last_error
+                "SELECT id, payload_json, attempt_count, next_attempt_at, last_error, account
                  FROM queue_items
                  WHERE status = 'pending' AND next_attempt_at <= ?1
-                 ORDER BY next_attempt_at ASC
+                   AND account NOT IN (SELECT value FROM json_each(?3))
+                 ORDER BY next_attempt_at ASC, id ASC
                  LIMIT ?2",
             )
             .map_err(|e| QueueStoreError::Db(e.to_string()))?;
 
         let rows = stmt
-            .query_map(params![now, limit as i64], map_row)
+            .query_map(params![now, limit as i64, paused], map_row)
             .map_err(|e| QueueStoreError::Db(e.to_string()))?;
 
         rows.collect::<Result<Vec<_>, _>>()
@@ -91,7 +149,7 @@ impl QueueStore {
         let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
         let mut stmt = conn
             .prepare(
-                "SELECT id, payload_json, attempt_count, next_attempt_at, last_error
+                "SELECT id, payload_json, attempt_count, next_attempt_at, last_error, account
                  FROM queue_items
                  WHERE status = 'pending'
                  ORDER BY created_at ASC
@@ -114,6 +172,68 @@ impl QueueStore {
         Ok(())
     }
 
+    /// Parks an update that would overwrite a newer remote version. The item
+    /// is not retried until the conflict is resolved.
+    pub fn mark_conflict(&self, id: i64, err: &str) -> Result<(), QueueStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        conn.execute(
+            "UPDATE queue_items SET status = 'conflict', updated_at = ?1, last_error = ?2
+             WHERE id = ?3",
+            params![now_unix(), err, id],
+        )
+        .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn conflicts(&self) -> Result<Vec<QueueItem>, QueueStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, payload_json, attempt_count, next_attempt_at, last_error, account
+                 FROM queue_items
+                 WHERE status = 'conflict'
+                 ORDER BY created_at ASC",
+            )
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], map_row)
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| QueueStoreError::Db(e.to_string()))
+    }
+
+    /// Drops a parked item without sending it. Returns `false` if there was
+    /// no conflict with this id.
+    pub fn discard_conflict(&self, id: i64) -> Result<bool, QueueStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        let removed = conn
+            .execute(
+                "DELETE FROM queue_items WHERE id = ?1 AND status = 'conflict'",
+                params![id],
+            )
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        Ok(removed > 0)
+    }
+
+    /// Replaces a parked item's operation and makes it due immediately.
+    pub fn requeue(&self, id: i64, operation: &QueueOperation) -> Result<(), QueueStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        let now = now_unix();
+        let payload_json =
+            serde_json::to_string(operation).map_err(|e| QueueStoreError::Serde(e.to_string()))?;
+        conn.execute(
+            "UPDATE queue_items
+             SET payload_json = ?1, status = 'pending', attempt_count = 0,
+                 next_attempt_at = ?2, updated_at = ?2, last_error = NULL
+             WHERE id = ?3",
+            params![payload_json, now, id],
+        )
+        .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
     pub fn mark_retry(
         &self,
         id: i64,
@@ -147,6 +267,31 @@ impl QueueStore {
         Ok(())
     }
 
+    /// Holds back an account's items after Pinboard rejected its token.
+    /// Returns `false` if the account was already paused.
+    pub fn pause_for_auth(&self, account: &str) -> bool {
+        self.paused_set().insert(account.to_string())
+    }
+
+    pub fn resume(&self, account: &str) {
+        self.paused_set().remove(account);
+    }
+
+    /// Lets every account's items through again, e.g. for a manual retry.
+    pub fn resume_all(&self) {
+        self.paused_set().clear();
+    }
+
+    pub fn paused_accounts(&self) -> Vec<String> {
+        self.paused_set().iter().cloned().collect()
+    }
+
+    fn paused_set(&self) -> MutexGuard<'_, BTreeSet<String>> {
+        self.auth_paused
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     pub fn stats(&self) -> Result<QueueStats, QueueStoreError> {
         let conn = open_db(&self.db_path).map_err(|e| QueueStoreError::Db(e.to_string()))?;
         let pending = conn
@@ -165,31 +310,56 @@ impl QueueStore {
             )
             .map_err(|e| QueueStoreError::Db(e.to_string()))?;
 
+        let conflicts = conn
+            .query_row(
+                "SELECT COUNT(*) FROM queue_items WHERE status = 'conflict'",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| QueueStoreError::Db(e.to_string()))?;
+
+        let paused_accounts = self.paused_accounts();
         Ok(QueueStats {
             pending: pending as u64,
             failed: failed as u64,
+            conflicts: conflicts as u64,
+            paused: !paused_accounts.is_empty(),
+            paused_accounts,
         })
     }
 }
 
 fn map_row(row: &Row<'_>) -> rusqlite::Result<QueueItem> {
     let payload_json: String = row.get(1)?;
-    let payload = serde_json::from_str(&payload_json).unwrap_or(BookmarkPayload {
-        url: String::new(),
-        title: String::new(),
-        notes: String::new(),
-        tags: Vec::new(),
-        private: false,
-        read_later: false,
-        intent: crate::domain::bookmark::SubmitIntent::Update,
-    });
+    let operation = parse_operation(&payload_json);
 
     Ok(QueueItem {
         id: row.get(0)?,
-        payload,
+        operation,
         attempt_count: row.get(2)?,
         next_attempt_at: row.get(3)?,
         last_error: row.get(4)?,
+        account: row.get(5)?,
+    })
+}
+
+/// Rows written before tag mutations were queueable hold a bare
+/// `BookmarkPayload`; read those as `AddBookmark`.
+fn parse_operation(payload_json: &str) -> QueueOperation {
+    serde_json::from_str::<QueueOperation>(payload_json).unwrap_or_else(|_| {
+        QueueOperation::AddBookmark {
+            payload: serde_json::from_str(payload_json).unwrap_or(BookmarkPayload {
+                url: String::new(),
+                title: String::new(),
+                notes: String::new(),
+                tags: Vec::new(),
+                private: false,
+                read_later: false,
+                intent: crate::domain::bookmark::SubmitIntent::Update,
+                created_at: None,
+                base: None,
+            }),
+        }
     })
 }
 
@@ -208,16 +378,12 @@ pub fn retry_delay_seconds(attempt: i64, retry_after_override: Option<i64>) -> i
     retry_after_override.unwrap_or(0).max(base)
 }
 
-fn now_unix() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0)
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{backoff_seconds, retry_delay_seconds};
+    use super::{
+        backoff_seconds, parse_operation, retry_delay_seconds, QueueOperation, QueueStore,
+    };
+    use crate::infra::db::TempDb;
 
     #[test]
     fn backoff_is_bounded() {
@@ -231,4 +397,83 @@ mod tests {
         assert_eq!(retry_delay_seconds(1, Some(120)), 120);
         assert_eq!(retry_delay_seconds(3, Some(5)), 180);
     }
+
+    #[test]
+    fn legacy_bookmark_rows_parse_as_add_bookmark() {
+        let legacy = r#"{"url":"https://example.com/","title":"Example","notes":"","tags":["a"],"private":false,"readLater":true,"intent":"create"}"#;
+        match parse_operation(legacy) {
+            QueueOperation::AddBookmark { payload } => {
+                assert_eq!(payload.title, "Example");
+                assert!(payload.read_later);
+            }
+            other => panic!("unexpected operation: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tag_operations_round_trip() {
+        let json = serde_json::to_string(&QueueOperation::RenameTag {
+            from: "js".to_string(),
+            to: "javascript".to_string(),
+        })
+        .unwrap();
+        assert!(json.contains(r#""kind":"renameTag""#));
+        assert!(matches!(
+            parse_operation(&json),
+            QueueOperation::RenameTag { from, to } if from == "js" && to == "javascript"
+        ));
+    }
+
+    #[test]
+    fn paused_accounts_are_skipped_until_resumed() {
+        let db = TempDb::new("queue");
+        let store = QueueStore::new(db.path().to_str().unwrap()).unwrap();
+        let delete = |url: &str| QueueOperation::DeleteBookmark {
+            url: url.to_string(),
+        };
+        store
+            .enqueue_batch("work", &[delete("https://a.example/")])
+            .unwrap();
+        store
+            .enqueue_batch("default", &[delete("https://b.example/")])
+            .unwrap();
+
+        assert!(store.pause_for_auth("work"));
+        assert!(!store.pause_for_auth("work"));
+        let due = store.due_items(10).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].account, "default");
+        assert_eq!(store.stats().unwrap().paused_accounts, vec!["work"]);
+
+        store.resume("work");
+        let accounts = store
+            .due_items(10)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.account)
+            .collect::<Vec<_>>();
+        assert_eq!(accounts, vec!["work", "default"]);
+    }
+
+    #[test]
+    fn only_parked_conflicts_can_be_discarded() {
+        let db = TempDb::new("queue-discard");
+        let store = QueueStore::new(db.path().to_str().unwrap()).unwrap();
+        let delete = |url: &str| QueueOperation::DeleteBookmark {
+            url: url.to_string(),
+        };
+        store
+            .enqueue_batch(
+                "default",
+                &[delete("https://a.example/"), delete("https://b.example/")],
+            )
+            .unwrap();
+        let items = store.list(10).unwrap();
+        store.mark_conflict(items[0].id, "changed").unwrap();
+
+        assert!(!store.discard_conflict(items[1].id).unwrap());
+        assert!(store.discard_conflict(items[0].id).unwrap());
+        assert!(store.conflicts().unwrap().is_empty());
+        assert_eq!(store.stats().unwrap().pending, 1);
+    }
 }
@@ -1,39 +1,64 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use tauri::{AppHandle, Emitter};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
 
-use crate::api::pinboard::PinboardError;
+use crate::api::pinboard::{PinboardClient, PinboardError};
+use crate::queue::store::QueueOperation;
+use crate::security::accounts::account_settings;
 use crate::AppState;
 
 const WORKER_TICK_SECS: u64 = 4;
 
-pub async fn run_background_worker(app: AppHandle, state: Arc<AppState>) {
+pub async fn run_background_worker<R: Runtime>(app: AppHandle<R>, state: Arc<AppState>) {
     loop {
         let _ = process_due_items(&app, &state, 1).await;
         tokio::time::sleep(Duration::from_secs(WORKER_TICK_SECS)).await;
     }
 }
 
-pub async fn process_due_items(
-    app: &AppHandle,
+pub async fn process_due_items<R: Runtime>(
+    app: &AppHandle<R>,
     state: &Arc<AppState>,
     limit: usize,
 ) -> Result<usize, String> {
-    let token = state
-        .token_store
-        .get_token()
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Pinboard token is not set".to_string())?;
-
     let due = state
         .queue_store
         .due_items(limit)
         .map_err(|e| e.to_string())?;
     let mut sent = 0usize;
+    let mut tokens = HashMap::new();
+    let accounts = account_settings(state)?;
 
     for item in due {
-        match state.pinboard.add_bookmark(&token, &item.payload).await {
+        if !tokens.contains_key(&item.account) {
+            // A credential backend that fails (a locked file, `pass` erroring)
+            // only holds back its own account.
+            let token = match state.token_store.get_token(&item.account) {
+                Ok(Some(token)) => Some(token),
+                Ok(None) => {
+                    let message =
+                        format!("No Pinboard token is saved for account {}", item.account);
+                    pause_for_auth(app, state, &item.account, message);
+                    None
+                }
+                Err(err) => {
+                    pause_for_auth(app, state, &item.account, err.message_for_user());
+                    None
+                }
+            };
+            tokens.insert(item.account.clone(), token);
+        }
+        let Some(token) = tokens[&item.account].as_deref() else {
+            continue;
+        };
+
+        let client = state
+            .pinboard
+            .for_account(&item.account, accounts.backend(&item.account));
+        match execute_operation(&client, token, &item.operation).await {
             Ok(_) => {
                 state
                     .queue_store
@@ -42,6 +67,19 @@ pub async fn process_due_items(
                 let _ = app.emit("queue:item_sent", item.id);
                 sent += 1;
             }
+            Err(err @ PinboardError::Conflict { .. }) => {
+                state
+                    .queue_store
+                    .mark_conflict(item.id, &err.message_for_user())
+                    .map_err(|e| e.to_string())?;
+                let _ = app.emit("queue:item_conflict", item.id);
+            }
+            Err(err @ PinboardError::Unauthorized { .. }) => {
+                // Every item of this account would fail the same way; leave
+                // them untouched until its token is replaced.
+                pause_for_auth(app, state, &item.account, err.message_for_user());
+                tokens.insert(item.account.clone(), None);
+            }
             Err(err) => {
                 let retry_after = err.retry_after_secs();
                 let should_break = matches!(err, PinboardError::RateLimited { .. });
@@ -69,3 +107,146 @@ pub async fn process_due_items(
 
     Ok(sent)
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthInvalid {
+    pub account: String,
+    pub message: String,
+}
+
+/// Pauses the account's queued items and asks the UI to re-enter its token.
+/// The event is only sent when the account was not paused already.
+pub fn pause_for_auth<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    account: &str,
+    message: String,
+) {
+    if state.queue_store.pause_for_auth(account) {
+        let _ = app.emit(
+            "auth:token_invalid",
+            AuthInvalid {
+                account: account.to_string(),
+                message,
+            },
+        );
+    }
+}
+
+pub async fn execute_operation(
+    client: &PinboardClient,
+    token: &str,
+    operation: &QueueOperation,
+) -> Result<(), PinboardError> {
+    match operation {
+        QueueOperation::AddBookmark { payload } => {
+            client.add_bookmark(token, payload).await.map(|_| ())
+        }
+        QueueOperation::DeleteBookmark { url } => client.delete_bookmark(token, url).await,
+        QueueOperation::RenameTag { from, to } => client.rename_tag(token, from, to).await,
+        QueueOperation::DeleteTag { tag } => client.delete_tag(token, tag).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::process_due_items;
+    use crate::api::mock_server::{app_state, MockPinboard, MockReply, TOKEN};
+    use crate::infra::db::now_unix;
+    use crate::queue::store::QueueOperation;
+    use crate::security::credentials::{CredentialError, CredentialStore};
+    use crate::AppState;
+
+    /// Holds `TOKEN` for every account but `work`, whose backend is locked.
+    struct WorkLocked;
+
+    impl CredentialStore for WorkLocked {
+        fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+            if account == "work" {
+                Err(CredentialError::Locked)
+            } else {
+                Ok(Some(TOKEN.to_string()))
+            }
+        }
+
+        fn set_token(&self, _account: &str, _token: &str) -> Result<(), CredentialError> {
+            Ok(())
+        }
+
+        fn clear_token(&self, _account: &str) -> Result<(), CredentialError> {
+            Ok(())
+        }
+    }
+
+    fn deletes(urls: &[&str]) -> Vec<QueueOperation> {
+        urls.iter()
+            .map(|url| QueueOperation::DeleteBookmark {
+                url: url.to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn failures_are_rescheduled_and_auth_errors_pause() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "worker");
+        let app = tauri::test::mock_app();
+        state
+            .queue_store
+            .enqueue_batch(
+                "default",
+                &deletes(&["https://a.example/", "https://b.example/"]),
+            )
+            .unwrap();
+
+        // A rate limit ends the run; the item waits for Retry-After.
+        server.reply(MockReply::RateLimited {
+            retry_after_secs: 600,
+        });
+        assert_eq!(process_due_items(app.handle(), &state, 5).await, Ok(0));
+        assert_eq!(server.requests_to("posts/delete").len(), 1);
+        let items = state.queue_store.list(10).unwrap();
+        let limited = items.iter().find(|item| item.attempt_count == 1).unwrap();
+        assert!(limited.next_attempt_at >= now_unix() + 590);
+
+        assert_eq!(process_due_items(app.handle(), &state, 5).await, Ok(1));
+        assert_eq!(state.queue_store.stats().unwrap().pending, 1);
+
+        state
+            .queue_store
+            .enqueue_batch("default", &deletes(&["https://c.example/"]))
+            .unwrap();
+        server.reply(MockReply::Status(401));
+        assert_eq!(process_due_items(app.handle(), &state, 5).await, Ok(0));
+        assert_eq!(state.queue_store.paused_accounts(), vec!["default"]);
+        assert_eq!(state.queue_store.stats().unwrap().pending, 2);
+    }
+
+    #[tokio::test]
+    async fn a_failing_credential_backend_only_pauses_its_account() {
+        let server = MockPinboard::start().await;
+        let (state, _db) = app_state(&server, "worker-locked");
+        let state = Arc::new(AppState {
+            token_store: Box::new(WorkLocked),
+            ..Arc::try_unwrap(state).ok().unwrap()
+        });
+        let app = tauri::test::mock_app();
+        state
+            .queue_store
+            .enqueue_batch("work", &deletes(&["https://a.example/"]))
+            .unwrap();
+        state
+            .queue_store
+            .enqueue_batch("default", &deletes(&["https://b.example/"]))
+            .unwrap();
+
+        assert_eq!(process_due_items(app.handle(), &state, 5).await, Ok(1));
+        assert_eq!(state.queue_store.paused_accounts(), vec!["work"]);
+        let deletes = server.requests_to("posts/delete");
+        assert_eq!(deletes.len(), 1);
+        assert_eq!(deletes[0].params["url"], "https://b.example/");
+    }
+}
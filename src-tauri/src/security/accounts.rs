@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use crate::api::pinboard::PinboardClient;
+use crate::domain::accounts::AccountSettings;
+use crate::settings::store::ACCOUNTS_KEY;
+use crate::AppState;
+
+/// The account new requests are made for, with its token and the client
+/// for its backend.
+pub struct Credentials {
+    pub account: String,
+    pub token: String,
+    pub client: Arc<PinboardClient>,
+}
+
+pub fn account_settings(state: &AppState) -> Result<AccountSettings, String> {
+    state
+        .settings_store
+        .get::<AccountSettings>(ACCOUNTS_KEY)
+        .map_err(|e| e.to_string())
+}
+
+pub fn active_credentials(state: &AppState) -> Result<Credentials, String> {
+    let settings = account_settings(state)?;
+    credentials_for(state, &settings, &settings.active)
+}
+
+/// Token and client for a named account, e.g. the one a queued item was
+/// recorded for.
+pub fn credentials_for(
+    state: &AppState,
+    settings: &AccountSettings,
+    account: &str,
+) -> Result<Credentials, String> {
+    let account = account.to_string();
+    let token = state
+        .token_store
+        .get_token(&account)
+        .map_err(|e| e.message_for_user())?
+        .ok_or_else(|| "Pinboard token is not set".to_string())?;
+    let client = state
+        .pinboard
+        .for_account(&account, settings.backend(&account));
+    Ok(Credentials {
+        account,
+        token,
+        client,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::active_credentials;
+    use crate::domain::accounts::AccountSettings;
+    use crate::infra::db::TempDb;
+    use crate::security::memory_store::MemoryStore;
+    use crate::security::token_store::CredentialControl;
+    use crate::settings::store::ACCOUNTS_KEY;
+    use crate::AppState;
+
+    #[test]
+    fn active_credentials_follow_the_selected_account() {
+        let db = TempDb::new("accounts");
+        let state = AppState::new(
+            db.path().to_str().unwrap(),
+            Arc::new(CredentialControl::new(
+                db.path().with_extension("credentials.json"),
+            )),
+            Box::new(MemoryStore::default()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            active_credentials(&state).err().as_deref(),
+            Some("Pinboard token is not set")
+        );
+
+        state.token_store.set_token("default", "me:AAAA").unwrap();
+        state.token_store.set_token("work", "work:BBBB").unwrap();
+        let credentials = active_credentials(&state).unwrap();
+        assert_eq!(credentials.account, "default");
+        assert_eq!(credentials.token, "me:AAAA");
+
+        let mut accounts = AccountSettings::default();
+        accounts.upsert("work", "work");
+        accounts.select("work").unwrap();
+        state.settings_store.set(ACCOUNTS_KEY, &accounts).unwrap();
+        assert_eq!(active_credentials(&state).unwrap().token, "work:BBBB");
+    }
+}
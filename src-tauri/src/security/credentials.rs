@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    #[error("system keyring is unavailable or locked (Secret Service): {0}")]
+    StorageUnavailable(String),
+    #[error("failed to access keyring: {0}")]
+    Keyring(String),
+    #[error("the credential file is locked")]
+    Locked,
+    #[error("wrong passphrase for the credential file")]
+    WrongPassphrase,
+    #[error("credential file error: {0}")]
+    File(String),
+    #[error("tokens from {0} are read-only here")]
+    ReadOnly(&'static str),
+    #[error("`pass` failed: {0}")]
+    Pass(String),
+    #[error("the pass entry cannot start with `-`")]
+    InvalidPassEntry,
+}
+
+impl CredentialError {
+    pub fn message_for_user(&self) -> String {
+        match self {
+            Self::StorageUnavailable(_) =>
+                "System keyring is unavailable or locked. Ensure a Secret Service provider (GNOME Keyring/KWallet) is running and unlocked, or switch token storage to an encrypted file.".to_string(),
+            Self::Locked => "The credential file is locked; enter its passphrase to unlock it.".to_string(),
+            Self::ReadOnly(source) => format!("Tokens from {source} are read-only; change the token there."),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Somewhere Pinboard tokens can be kept, one per account.
+pub trait CredentialStore: Send + Sync {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError>;
+    fn set_token(&self, account: &str, token: &str) -> Result<(), CredentialError>;
+    fn clear_token(&self, account: &str) -> Result<(), CredentialError>;
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialStatus {
+    pub settings: CredentialSettings,
+    pub file_exists: bool,
+    pub file_unlocked: bool,
+}
+
+/// Where saved tokens are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialBackend {
+    /// Secret Service via the system keyring.
+    #[default]
+    Keyring,
+    /// A passphrase-encrypted file in the config directory.
+    File,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSettings {
+    #[serde(default)]
+    pub backend: CredentialBackend,
+    /// Read tokens from `OMMAPIN_PINBOARD_TOKEN[_<ACCOUNT>]` before anything else.
+    #[serde(default)]
+    pub allow_env: bool,
+    /// A `pass` entry to read tokens from, with `{account}` replaced by the
+    /// account name. Checked after the environment and before `backend`.
+    #[serde(default)]
+    pub pass_entry: Option<String>,
+}
+
+impl CredentialSettings {
+    /// Trims the pass entry, dropping an empty one, and rejects an entry
+    /// `pass` would read as an option.
+    pub fn validated(self) -> Result<Self, CredentialError> {
+        let pass_entry = self
+            .pass_entry
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty());
+        if pass_entry
+            .as_deref()
+            .is_some_and(|entry| entry.starts_with('-'))
+        {
+            return Err(CredentialError::InvalidPassEntry);
+        }
+        Ok(Self { pass_entry, ..self })
+    }
+}
@@ -0,0 +1,122 @@
+use std::process::Command;
+
+use crate::domain::accounts::DEFAULT_ACCOUNT;
+use crate::security::credentials::{CredentialError, CredentialStore};
+
+const ENV_PREFIX: &str = "OMMAPIN_PINBOARD_TOKEN";
+
+/// Tokens from the environment: `OMMAPIN_PINBOARD_TOKEN` for the default
+/// account, `OMMAPIN_PINBOARD_TOKEN_WORK` for an account named `work`.
+pub struct EnvStore {
+    /// Reads a variable; the process environment outside tests.
+    lookup: fn(&str) -> Option<String>,
+}
+
+impl Default for EnvStore {
+    fn default() -> Self {
+        Self {
+            lookup: |name| std::env::var(name).ok(),
+        }
+    }
+}
+
+impl EnvStore {
+    pub fn variable(account: &str) -> String {
+        if account == DEFAULT_ACCOUNT {
+            ENV_PREFIX.to_string()
+        } else {
+            format!(
+                "{ENV_PREFIX}_{}",
+                account.to_ascii_uppercase().replace('-', "_")
+            )
+        }
+    }
+}
+
+impl CredentialStore for EnvStore {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        Ok((self.lookup)(&Self::variable(account))
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty()))
+    }
+
+    fn set_token(&self, _account: &str, _token: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::ReadOnly("the environment"))
+    }
+
+    fn clear_token(&self, _account: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::ReadOnly("the environment"))
+    }
+}
+
+/// Tokens from the first line of `pass show <entry>`, where `{account}` in
+/// the entry is replaced by the account name.
+pub struct PassStore {
+    pub entry: String,
+}
+
+impl PassStore {
+    fn entry_for(&self, account: &str) -> String {
+        self.entry.replace("{account}", account)
+    }
+}
+
+impl CredentialStore for PassStore {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        let output = Command::new("pass")
+            .args(["show", &self.entry_for(account)])
+            .output()
+            .map_err(|e| CredentialError::Pass(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Accounts without an entry fall through to the next store.
+            if stderr.contains("is not in the password store") {
+                return Ok(None);
+            }
+            return Err(CredentialError::Pass(stderr.trim().to_string()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty()))
+    }
+
+    fn set_token(&self, _account: &str, _token: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::ReadOnly("pass"))
+    }
+
+    fn clear_token(&self, _account: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::ReadOnly("pass"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvStore;
+    use crate::security::credentials::{CredentialError, CredentialStore};
+
+    #[test]
+    fn env_variables_are_named_after_the_account() {
+        assert_eq!(EnvStore::variable("default"), "OMMAPIN_PINBOARD_TOKEN");
+        assert_eq!(
+            EnvStore::variable("work-2"),
+            "OMMAPIN_PINBOARD_TOKEN_WORK_2"
+        );
+
+        let store = EnvStore {
+            lookup: |name| {
+                (name == "OMMAPIN_PINBOARD_TOKEN_ENV_TEST").then(|| " bob:ABC \n".to_string())
+            },
+        };
+        assert_eq!(
+            store.get_token("env-test").unwrap().as_deref(),
+            Some("bob:ABC")
+        );
+        assert_eq!(store.get_token("default").unwrap(), None);
+        assert!(matches!(
+            store.set_token("env-test", "x"),
+            Err(CredentialError::ReadOnly(_))
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::security::credentials::{CredentialError, CredentialStore};
+use crate::security::sealed::{self, Kdf, SealError, SealedSecret};
+
+const FILE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CredentialFile {
+    version: u32,
+    /// JSON map of account name to token, sealed as a whole.
+    tokens: SealedSecret,
+}
+
+struct Unlocked {
+    passphrase: String,
+    tokens: BTreeMap<String, String>,
+}
+
+/// Tokens in `~/.config/ommapin/credentials.json`, encrypted with a
+/// passphrase (Argon2id and AES-256-GCM, see `sealed`). The file is
+/// decrypted once by `unlock` and kept in memory until the app exits.
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    kdf: Kdf,
+    unlocked: Mutex<Option<Unlocked>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            kdf: Kdf::ARGON2ID,
+            unlocked: Mutex::new(None),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_kdf(path: PathBuf, kdf: Kdf) -> Self {
+        Self {
+            kdf,
+            ..Self::new(path)
+        }
+    }
+
+    pub fn default_path() -> PathBuf {
+        let config_root = std::env::var("XDG_CONFIG_HOME")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".config")
+            });
+        config_root.join("ommapin").join("credentials.json")
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.state().is_some()
+    }
+
+    /// Decrypts the file with `passphrase`. Without a file yet, the
+    /// passphrase is used for the one written by the first saved token.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), CredentialError> {
+        if passphrase.is_empty() {
+            return Err(CredentialError::File(
+                "the passphrase cannot be empty".to_string(),
+            ));
+        }
+        let tokens = if self.exists() {
+            read_tokens(&self.path, passphrase)?
+        } else {
+            BTreeMap::new()
+        };
+        *self.state() = Some(Unlocked {
+            passphrase: passphrase.to_string(),
+            tokens,
+        });
+        Ok(())
+    }
+
+    fn state(&self) -> MutexGuard<'_, Option<Unlocked>> {
+        self.unlocked
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self, unlocked: &Unlocked) -> Result<(), CredentialError> {
+        let plaintext = serde_json::to_string(&unlocked.tokens)
+            .map_err(|e| CredentialError::File(e.to_string()))?;
+        let tokens = sealed::seal_with(&unlocked.passphrase, &plaintext, self.kdf)
+            .map_err(|e| CredentialError::File(e.to_string()))?;
+        let json = serde_json::to_string_pretty(&CredentialFile {
+            version: FILE_VERSION,
+            tokens,
+        })
+        .map_err(|e| CredentialError::File(e.to_string()))?;
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| CredentialError::File(e.to_string()))?;
+        }
+        // Written next to the target and renamed, so a crash never leaves a
+        // half-written file; readable by the owner only.
+        let tmp = self.path.with_extension("json.tmp");
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp)
+            .map_err(|e| CredentialError::File(e.to_string()))?;
+        file.write_all(json.as_bytes())
+            .and_then(|()| file.sync_all())
+            .map_err(|e| CredentialError::File(e.to_string()))?;
+        fs::rename(&tmp, &self.path).map_err(|e| CredentialError::File(e.to_string()))
+    }
+}
+
+fn read_tokens(path: &Path, passphrase: &str) -> Result<BTreeMap<String, String>, CredentialError> {
+    let content = fs::read_to_string(path).map_err(|e| CredentialError::File(e.to_string()))?;
+    let file = serde_json::from_str::<CredentialFile>(&content)
+        .map_err(|e| CredentialError::File(e.to_string()))?;
+    if file.version > FILE_VERSION {
+        return Err(CredentialError::File(format!(
+            "unsupported credential file version {}",
+            file.version
+        )));
+    }
+    let plaintext = sealed::open(passphrase, &file.tokens).map_err(|err| match err {
+        SealError::WrongPassphrase => CredentialError::WrongPassphrase,
+        other => CredentialError::File(other.to_string()),
+    })?;
+    serde_json::from_str(&plaintext).map_err(|e| CredentialError::File(e.to_string()))
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        let state = self.state();
+        let unlocked = state.as_ref().ok_or(CredentialError::Locked)?;
+        Ok(unlocked.tokens.get(account).cloned())
+    }
+
+    fn set_token(&self, account: &str, token: &str) -> Result<(), CredentialError> {
+        let mut state = self.state();
+        let unlocked = state.as_mut().ok_or(CredentialError::Locked)?;
+        unlocked
+            .tokens
+            .insert(account.to_string(), token.to_string());
+        self.write(unlocked)
+    }
+
+    fn clear_token(&self, account: &str) -> Result<(), CredentialError> {
+        let mut state = self.state();
+        let unlocked = state.as_mut().ok_or(CredentialError::Locked)?;
+        if unlocked.tokens.remove(account).is_some() {
+            self.write(unlocked)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::EncryptedFileStore;
+    use crate::infra::db::TempDb;
+    use crate::security::credentials::{CredentialError, CredentialStore};
+    use crate::security::sealed::Kdf;
+
+    #[test]
+    fn tokens_survive_reopening_only_with_the_passphrase() {
+        let file = TempDb::new("credentials");
+
+        let kdf = Kdf::Argon2id {
+            memory_kib: 64,
+            iterations: 1,
+        };
+        let store = EncryptedFileStore::with_kdf(file.path().clone(), kdf);
+        assert!(matches!(
+            store.get_token("default"),
+            Err(CredentialError::Locked)
+        ));
+        store.unlock("hunter2").unwrap();
+        store.set_token("work", "alice:SECRET").unwrap();
+        let written = std::fs::read_to_string(file.path()).unwrap();
+        assert!(!written.contains("alice:SECRET"));
+        assert!(written.contains("\"kdf\": \"argon2id\""));
+        assert_eq!(
+            std::fs::metadata(file.path()).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        let reopened = EncryptedFileStore::new(file.path().clone());
+        assert!(matches!(
+            reopened.unlock("wrong"),
+            Err(CredentialError::WrongPassphrase)
+        ));
+        reopened.unlock("hunter2").unwrap();
+        assert_eq!(
+            reopened.get_token("work").unwrap().as_deref(),
+            Some("alice:SECRET")
+        );
+        assert_eq!(reopened.get_token("default").unwrap(), None);
+    }
+}
@@ -0,0 +1,54 @@
+use keyring::{Entry, Error as KeyringError};
+
+use crate::domain::accounts::DEFAULT_ACCOUNT;
+use crate::security::credentials::{CredentialError, CredentialStore};
+
+const SERVICE: &str = "ommapin";
+const USERNAME: &str = "pinboard_auth_token";
+
+/// Pinboard tokens in the system keyring, one entry per account.
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// The default account keeps the entry name used before accounts existed.
+    fn entry(account: &str) -> Result<Entry, CredentialError> {
+        let username = if account == DEFAULT_ACCOUNT {
+            USERNAME.to_string()
+        } else {
+            format!("{USERNAME}:{account}")
+        };
+        Entry::new(SERVICE, &username).map_err(|e| CredentialError::Keyring(e.to_string()))
+    }
+
+    fn map_keyring_error(error: KeyringError) -> CredentialError {
+        match error {
+            KeyringError::NoStorageAccess(inner) => {
+                CredentialError::StorageUnavailable(inner.to_string())
+            }
+            other => CredentialError::Keyring(other.to_string()),
+        }
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn set_token(&self, account: &str, token: &str) -> Result<(), CredentialError> {
+        Self::entry(account)?
+            .set_password(token)
+            .map_err(Self::map_keyring_error)
+    }
+
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        match Self::entry(account)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(KeyringError::NoEntry) => Ok(None),
+            Err(err) => Err(Self::map_keyring_error(err)),
+        }
+    }
+
+    fn clear_token(&self, account: &str) -> Result<(), CredentialError> {
+        match Self::entry(account)?.delete_credential() {
+            Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+            Err(err) => Err(Self::map_keyring_error(err)),
+        }
+    }
+}
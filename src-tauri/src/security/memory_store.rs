@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::security::credentials::{CredentialError, CredentialStore};
+
+/// Tokens kept in a map, for tests that should not touch the keyring.
+#[derive(Default)]
+pub struct MemoryStore {
+    tokens: Mutex<HashMap<String, String>>,
+}
+
+impl CredentialStore for MemoryStore {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        Ok(self.tokens.lock().unwrap().get(account).cloned())
+    }
+
+    fn set_token(&self, account: &str, token: &str) -> Result<(), CredentialError> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(account.to_string(), token.to_string());
+        Ok(())
+    }
+
+    fn clear_token(&self, account: &str) -> Result<(), CredentialError> {
+        self.tokens.lock().unwrap().remove(account);
+        Ok(())
+    }
+}
@@ -1 +1,9 @@
+pub mod accounts;
+pub mod credentials;
+pub mod external;
+pub mod file_store;
+pub mod keyring_store;
+#[cfg(test)]
+pub mod memory_store;
+pub mod sealed;
 pub mod token_store;
@@ -0,0 +1,183 @@
+use std::num::NonZeroU32;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+const PBKDF2_NAME: &str = "pbkdf2-sha256";
+const ARGON2ID_NAME: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SealError {
+    #[error("wrong passphrase or corrupted secret")]
+    WrongPassphrase,
+    #[error("invalid sealed secret: {0}")]
+    Invalid(String),
+    #[error("could not generate random bytes")]
+    Random,
+}
+
+/// How the key is derived from the passphrase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Pbkdf2 { iterations: u32 },
+    Argon2id { memory_kib: u32, iterations: u32 },
+}
+
+impl Kdf {
+    /// PBKDF2-HMAC-SHA256, used for backup archives.
+    pub const PBKDF2: Kdf = Kdf::Pbkdf2 {
+        iterations: 600_000,
+    };
+    /// Argon2id at OWASP's recommended minimum (19 MiB, two passes, one
+    /// lane), used for the credential file.
+    pub const ARGON2ID: Kdf = Kdf::Argon2id {
+        memory_kib: 19_456,
+        iterations: 2,
+    };
+}
+
+/// A secret encrypted with AES-256-GCM under a key derived from a
+/// passphrase. Everything needed to open it again, except the passphrase,
+/// is stored alongside.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SealedSecret {
+    pub kdf: String,
+    pub iterations: u32,
+    /// Argon2 memory cost; absent for PBKDF2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_kib: Option<u32>,
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+pub fn seal(passphrase: &str, plaintext: &str) -> Result<SealedSecret, SealError> {
+    seal_with(passphrase, plaintext, Kdf::PBKDF2)
+}
+
+pub fn seal_with(passphrase: &str, plaintext: &str, kdf: Kdf) -> Result<SealedSecret, SealError> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| SealError::Random)?;
+    rng.fill(&mut nonce).map_err(|_| SealError::Random)?;
+
+    let key = derive_key(passphrase, &salt, kdf)?;
+    let mut buffer = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut buffer,
+    )
+    .map_err(|_| SealError::Invalid("encryption failed".to_string()))?;
+
+    let (kdf_name, iterations, memory_kib) = match kdf {
+        Kdf::Pbkdf2 { iterations } => (PBKDF2_NAME, iterations, None),
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+        } => (ARGON2ID_NAME, iterations, Some(memory_kib)),
+    };
+    Ok(SealedSecret {
+        kdf: kdf_name.to_string(),
+        iterations,
+        memory_kib,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(buffer),
+    })
+}
+
+pub fn open(passphrase: &str, sealed: &SealedSecret) -> Result<String, SealError> {
+    let kdf = match (sealed.kdf.as_str(), sealed.memory_kib) {
+        (PBKDF2_NAME, _) => Kdf::Pbkdf2 {
+            iterations: sealed.iterations,
+        },
+        (ARGON2ID_NAME, Some(memory_kib)) => Kdf::Argon2id {
+            memory_kib,
+            iterations: sealed.iterations,
+        },
+        (name, _) => {
+            return Err(SealError::Invalid(format!(
+                "unsupported key derivation `{name}`"
+            )))
+        }
+    };
+    let decode = |field: &str| {
+        STANDARD
+            .decode(field)
+            .map_err(|e| SealError::Invalid(e.to_string()))
+    };
+    let salt = decode(&sealed.salt)?;
+    let nonce = Nonce::try_assume_unique_for_key(&decode(&sealed.nonce)?)
+        .map_err(|_| SealError::Invalid("bad nonce length".to_string()))?;
+    let mut buffer = decode(&sealed.ciphertext)?;
+
+    let key = derive_key(passphrase, &salt, kdf)?;
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut buffer)
+        .map_err(|_| SealError::WrongPassphrase)?;
+    String::from_utf8(plaintext.to_vec()).map_err(|e| SealError::Invalid(e.to_string()))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: Kdf) -> Result<LessSafeKey, SealError> {
+    let mut key = [0u8; KEY_LEN];
+    match kdf {
+        Kdf::Pbkdf2 { iterations } => {
+            let iterations = NonZeroU32::new(iterations)
+                .ok_or_else(|| SealError::Invalid("zero KDF iterations".to_string()))?;
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                iterations,
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            );
+        }
+        Kdf::Argon2id {
+            memory_kib,
+            iterations,
+        } => {
+            let params = Params::new(memory_kib, iterations, 1, Some(KEY_LEN))
+                .map_err(|e| SealError::Invalid(e.to_string()))?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| SealError::Invalid(e.to_string()))?;
+        }
+    }
+    UnboundKey::new(&AES_256_GCM, &key)
+        .map(LessSafeKey::new)
+        .map_err(|_| SealError::Invalid("bad key length".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open, seal_with, Kdf, SealError};
+
+    #[test]
+    fn opens_only_with_the_right_passphrase() {
+        let cheap = [
+            Kdf::Pbkdf2 { iterations: 1_000 },
+            Kdf::Argon2id {
+                memory_kib: 64,
+                iterations: 1,
+            },
+        ];
+        for kdf in cheap {
+            let sealed = seal_with("correct horse", "user:TOKEN", kdf).unwrap();
+            assert_ne!(sealed.ciphertext, "user:TOKEN");
+            assert_eq!(open("correct horse", &sealed).unwrap(), "user:TOKEN");
+            assert!(matches!(
+                open("wrong", &sealed),
+                Err(SealError::WrongPassphrase)
+            ));
+        }
+    }
+}
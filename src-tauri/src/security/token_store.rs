@@ -1,57 +1,106 @@
-use keyring::{Entry, Error as KeyringError};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
 
-const SERVICE: &str = "ommapin";
-const USERNAME: &str = "pinboard_auth_token";
+use crate::security::credentials::{
+    CredentialBackend, CredentialError, CredentialSettings, CredentialStatus, CredentialStore,
+};
+use crate::security::external::{EnvStore, PassStore};
+use crate::security::file_store::EncryptedFileStore;
+use crate::security::keyring_store::KeyringStore;
 
-#[derive(Debug, thiserror::Error)]
-pub enum TokenStoreError {
-    #[error("system keyring is unavailable or locked (Secret Service): {0}")]
-    StorageUnavailable(String),
-    #[error("failed to access keyring: {0}")]
-    Keyring(String),
+/// What the credential commands change: the saved `CredentialSettings` and
+/// the encrypted file, which can be unlocked whichever backend is selected.
+/// `TokenStore` reads tokens through the same instance.
+pub struct CredentialControl {
+    settings: RwLock<CredentialSettings>,
+    file: EncryptedFileStore,
 }
 
-pub struct TokenStore;
-
-impl TokenStore {
-    pub fn new() -> Self {
-        Self
+impl CredentialControl {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            settings: RwLock::new(CredentialSettings::default()),
+            file: EncryptedFileStore::new(file_path),
+        }
     }
 
-    pub fn set_token(&self, token: &str) -> Result<(), TokenStoreError> {
-        Entry::new(SERVICE, USERNAME)
-            .map_err(|e| TokenStoreError::Keyring(e.to_string()))?
-            .set_password(token)
-            .map_err(Self::map_keyring_error)
+    pub fn configure(&self, settings: CredentialSettings) {
+        *self
+            .settings
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = settings;
     }
 
-    pub fn get_token(&self) -> Result<Option<String>, TokenStoreError> {
-        let entry =
-            Entry::new(SERVICE, USERNAME).map_err(|e| TokenStoreError::Keyring(e.to_string()))?;
+    /// Unlocks the encrypted file, whichever backend is selected.
+    pub fn unlock(&self, passphrase: &str) -> Result<(), CredentialError> {
+        self.file.unlock(passphrase)
+    }
 
-        match entry.get_password() {
-            Ok(value) => Ok(Some(value)),
-            Err(KeyringError::NoEntry) => Ok(None),
-            Err(err) => Err(Self::map_keyring_error(err)),
+    pub fn status(&self) -> CredentialStatus {
+        CredentialStatus {
+            settings: self.settings().clone(),
+            file_exists: self.file.exists(),
+            file_unlocked: self.file.is_unlocked(),
         }
     }
 
-    pub fn clear_token(&self) -> Result<(), TokenStoreError> {
-        let entry =
-            Entry::new(SERVICE, USERNAME).map_err(|e| TokenStoreError::Keyring(e.to_string()))?;
+    fn settings(&self) -> RwLockReadGuard<'_, CredentialSettings> {
+        self.settings
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The credential stores in the order set by `CredentialSettings`: tokens
+/// are read from the environment (if allowed), then `pass` (if configured),
+/// then the chosen backend. Saved tokens always go to the backend.
+pub struct TokenStore {
+    control: Arc<CredentialControl>,
+    keyring: KeyringStore,
+    env: EnvStore,
+}
 
-        match entry.delete_credential() {
-            Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
-            Err(err) => Err(Self::map_keyring_error(err)),
+impl TokenStore {
+    pub fn new(control: Arc<CredentialControl>) -> Self {
+        Self {
+            control,
+            keyring: KeyringStore,
+            env: EnvStore::default(),
         }
     }
 
-    fn map_keyring_error(error: KeyringError) -> TokenStoreError {
-        match error {
-            KeyringError::NoStorageAccess(inner) => {
-                TokenStoreError::StorageUnavailable(inner.to_string())
+    fn backend(&self) -> &dyn CredentialStore {
+        match self.control.settings().backend {
+            CredentialBackend::Keyring => &self.keyring,
+            CredentialBackend::File => &self.control.file,
+        }
+    }
+}
+
+impl CredentialStore for TokenStore {
+    fn get_token(&self, account: &str) -> Result<Option<String>, CredentialError> {
+        let (allow_env, pass_entry) = {
+            let settings = self.control.settings();
+            (settings.allow_env, settings.pass_entry.clone())
+        };
+        if allow_env {
+            if let Some(token) = self.env.get_token(account)? {
+                return Ok(Some(token));
+            }
+        }
+        if let Some(entry) = pass_entry.filter(|entry| !entry.trim().is_empty()) {
+            if let Some(token) = (PassStore { entry }).get_token(account)? {
+                return Ok(Some(token));
             }
-            other => TokenStoreError::Keyring(other.to_string()),
         }
+        self.backend().get_token(account)
+    }
+
+    fn set_token(&self, account: &str, token: &str) -> Result<(), CredentialError> {
+        self.backend().set_token(account, token)
+    }
+
+    fn clear_token(&self, account: &str) -> Result<(), CredentialError> {
+        self.backend().clear_token(account)
     }
 }
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use rusqlite::{params, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::infra::db::{database_path, now_unix, open_db};
+
+pub const DOMAIN_RULES_KEY: &str = "domain_rules";
+pub const TAG_NORMALIZATION_KEY: &str = "tag_normalization";
+pub const TAG_BUNDLES_KEY: &str = "tag_bundles";
+pub const ACCOUNTS_KEY: &str = "accounts";
+pub const CREDENTIALS_KEY: &str = "credential_storage";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsStoreError {
+    #[error("db error: {0}")]
+    Db(String),
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+pub struct SettingsStore {
+    db_path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(custom_path: &str) -> Result<Self, SettingsStoreError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| SettingsStoreError::Db(e.to_string()))?;
+        Ok(Self { db_path })
+    }
+
+    pub fn get<T: DeserializeOwned + Default>(&self, key: &str) -> Result<T, SettingsStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| SettingsStoreError::Db(e.to_string()))?;
+        let value_json = conn
+            .query_row(
+                "SELECT value_json FROM settings WHERE key = ?1",
+                params![key],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|e| SettingsStoreError::Db(e.to_string()))?;
+
+        match value_json {
+            Some(json) => {
+                serde_json::from_str(&json).map_err(|e| SettingsStoreError::Serde(e.to_string()))
+            }
+            None => Ok(T::default()),
+        }
+    }
+
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) -> Result<(), SettingsStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| SettingsStoreError::Db(e.to_string()))?;
+        let value_json =
+            serde_json::to_string(value).map_err(|e| SettingsStoreError::Serde(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO settings(key, value_json, updated_at) VALUES(?1, ?2, ?3)
+             ON CONFLICT(key) DO UPDATE SET value_json = excluded.value_json, updated_at = excluded.updated_at",
+            params![key, value_json, now_unix()],
+        )
+        .map_err(|e| SettingsStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rusqlite::{params, OptionalExtension};
+
+use crate::domain::bookmark::UserTag;
+use crate::infra::db::{database_path, now_unix, open_db};
+
+const USER_TAGS_SYNC_NAME: &str = "user_tags";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TagCacheError {
+    #[error("db error: {0}")]
+    Db(String),
+}
+
+/// SQLite copy of the user's Pinboard tag list (`tags/get`) with counts.
+pub struct TagCacheStore {
+    db_path: PathBuf,
+    refreshing: AtomicBool,
+}
+
+impl TagCacheStore {
+    pub fn new(custom_path: &str) -> Result<Self, TagCacheError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        Ok(Self {
+            db_path,
+            refreshing: AtomicBool::new(false),
+        })
+    }
+
+    pub fn list(&self) -> Result<Vec<UserTag>, TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        let mut stmt = conn
+            .prepare("SELECT tag, count FROM user_tags ORDER BY count DESC, tag ASC")
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(UserTag {
+                    tag: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| TagCacheError::Db(e.to_string()))
+    }
+
+    pub fn fetched_at(&self) -> Result<Option<i64>, TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        conn.query_row(
+            "SELECT fetched_at FROM sync_state WHERE name = ?1",
+            params![USER_TAGS_SYNC_NAME],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| TagCacheError::Db(e.to_string()))
+    }
+
+    pub fn is_stale(&self, max_age_secs: i64) -> Result<bool, TagCacheError> {
+        Ok(match self.fetched_at()? {
+            Some(fetched_at) => now_unix() - fetched_at >= max_age_secs,
+            None => true,
+        })
+    }
+
+    /// Replaces the cache with a fresh `tags/get` result.
+    pub fn replace_all(&self, tags: &[UserTag]) -> Result<(), TagCacheError> {
+        let mut conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        let now = now_unix();
+        let tx = conn
+            .transaction()
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        tx.execute("DELETE FROM user_tags", [])
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR REPLACE INTO user_tags(tag, count, updated_at) VALUES(?1, ?2, ?3)",
+                params![tag.tag, tag.count, now],
+            )
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        }
+        tx.execute(
+            "INSERT INTO sync_state(name, fetched_at) VALUES(?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET fetched_at = excluded.fetched_at",
+            params![USER_TAGS_SYNC_NAME, now],
+        )
+        .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        tx.commit().map_err(|e| TagCacheError::Db(e.to_string()))
+    }
+
+    /// Counts tags from a locally submitted or queued bookmark so the cache
+    /// stays useful until the next refresh.
+    pub fn record_usage(&self, tags: &[String]) -> Result<(), TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        let now = now_unix();
+        for tag in tags {
+            conn.execute(
+                "INSERT INTO user_tags(tag, count, updated_at) VALUES(?1, 1, ?2)
+                 ON CONFLICT(tag) DO UPDATE SET count = count + 1, updated_at = excluded.updated_at",
+                params![tag, now],
+            )
+            .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Mirrors `tags/rename` locally, folding counts into the target tag.
+    pub fn rename(&self, from: &str, to: &str) -> Result<(), TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO user_tags(tag, count, updated_at)
+             SELECT ?2, count, ?3 FROM user_tags WHERE tag = ?1 COLLATE NOCASE AND tag != ?2
+             ON CONFLICT(tag) DO UPDATE SET count = count + excluded.count, updated_at = excluded.updated_at",
+            params![from, to, now_unix()],
+        )
+        .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM user_tags WHERE tag = ?1 COLLATE NOCASE AND tag != ?2",
+            params![from, to],
+        )
+        .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn remove(&self, tag: &str) -> Result<(), TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM user_tags WHERE tag = ?1 COLLATE NOCASE",
+            params![tag],
+        )
+        .map_err(|e| TagCacheError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), TagCacheError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagCacheError::Db(e.to_string()))?;
+        conn.execute_batch(&format!(
+            "DELETE FROM user_tags; DELETE FROM sync_state WHERE name = '{USER_TAGS_SYNC_NAME}';"
+        ))
+        .map_err(|e| TagCacheError::Db(e.to_string()))
+    }
+
+    /// Returns true if the caller won the right to run a refresh.
+    pub fn begin_refresh(&self) -> bool {
+        self.refreshing
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    pub fn end_refresh(&self) {
+        self.refreshing.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::accounts::DEFAULT_ACCOUNT;
+use crate::infra::db::{database_path, now_unix, open_db};
+
+#[derive(Debug, thiserror::Error)]
+pub enum TagHistoryError {
+    #[error("db error: {0}")]
+    Db(String),
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    #[serde(default = "default_account")]
+    pub account: String,
+    pub host: String,
+    pub tags: Vec<String>,
+    pub created_at: i64,
+}
+
+fn default_account() -> String {
+    DEFAULT_ACCOUNT.to_string()
+}
+
+/// Local record of which tags were used on which host, for suggestion
+/// ranking. Entries are kept per account so one account's tags are never
+/// suggested in another.
+pub struct TagHistoryStore {
+    db_path: PathBuf,
+}
+
+impl TagHistoryStore {
+    pub fn new(custom_path: &str) -> Result<Self, TagHistoryError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| TagHistoryError::Db(e.to_string()))?;
+        Ok(Self { db_path })
+    }
+
+    pub fn record(
+        &self,
+        account: &str,
+        host: &str,
+        tags: &[String],
+    ) -> Result<(), TagHistoryError> {
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let conn = open_db(&self.db_path).map_err(|e| TagHistoryError::Db(e.to_string()))?;
+        let tags_json =
+            serde_json::to_string(tags).map_err(|e| TagHistoryError::Serde(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO tag_history(account, host, tags_json, created_at) VALUES(?1, ?2, ?3, ?4)",
+            params![account, host, tags_json, now_unix()],
+        )
+        .map_err(|e| TagHistoryError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn recent(
+        &self,
+        account: &str,
+        limit: usize,
+    ) -> Result<Vec<HistoryEntry>, TagHistoryError> {
+        let conn = open_db(&self.db_path).map_err(|e| TagHistoryError::Db(e.to_string()))?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT account, host, tags_json, created_at
+                 FROM tag_history
+                 WHERE account = ?1
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| TagHistoryError::Db(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![account, limit as i64], |row| {
+                let tags_json: String = row.get(2)?;
+                Ok(HistoryEntry {
+                    account: row.get(0)?,
+                    host: row.get(1)?,
+                    tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| TagHistoryError::Db(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| TagHistoryError::Db(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TagHistoryStore;
+    use crate::infra::db::TempDb;
+
+    #[test]
+    fn history_is_kept_per_account() {
+        let db = TempDb::new("tag-history");
+        let store = TagHistoryStore::new(db.path().to_str().unwrap()).unwrap();
+
+        store
+            .record("default", "docs.rs", &["rust".to_string()])
+            .unwrap();
+        store
+            .record("work", "intranet.example", &[".payroll".to_string()])
+            .unwrap();
+
+        let personal = store.recent("default", 10).unwrap();
+        assert_eq!(personal.len(), 1);
+        assert_eq!(personal[0].tags, vec!["rust"]);
+        assert_eq!(store.recent("work", 10).unwrap()[0].account, "work");
+        assert!(store.recent("other", 10).unwrap().is_empty());
+    }
+}
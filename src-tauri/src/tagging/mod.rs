@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod history;
+pub mod ranking;
+pub mod service;
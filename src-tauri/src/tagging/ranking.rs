@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::domain::bookmark::{TagSuggestions, UserTag};
+use crate::domain::tags::Tag;
+use crate::tagging::history::HistoryEntry;
+
+const WEIGHT_COUNT: f64 = 1.0;
+const WEIGHT_CO_OCCURRENCE: f64 = 3.0;
+const WEIGHT_SAME_HOST: f64 = 4.0;
+const WEIGHT_TITLE_MATCH: f64 = 5.0;
+const WEIGHT_RECENCY: f64 = 2.0;
+const WEIGHT_PINBOARD_RECOMMENDED: f64 = 6.0;
+const WEIGHT_PINBOARD_POPULAR: f64 = 2.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+const SECS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RankedTag {
+    pub tag: String,
+    pub score: f64,
+}
+
+pub struct RankingContext<'a> {
+    pub entered: &'a [String],
+    pub host: Option<&'a str>,
+    pub title: &'a str,
+    pub now: i64,
+    /// Private `.` tags are only suggested when asked for, so they do not
+    /// leak onto public bookmarks by a stray click.
+    pub include_private: bool,
+}
+
+struct Candidate {
+    tag: String,
+    score: f64,
+}
+
+/// Ranks candidate tags from the user's vocabulary, local history and
+/// Pinboard's suggestions. Scores are additive; ties sort by tag name so the
+/// output is stable for the same inputs. Private tags are left out unless
+/// the context asks for them.
+pub fn rank_tags(
+    context: &RankingContext<'_>,
+    user_tags: &[UserTag],
+    history: &[HistoryEntry],
+    pinboard: Option<&TagSuggestions>,
+    limit: usize,
+) -> Vec<RankedTag> {
+    let entered = context
+        .entered
+        .iter()
+        .filter_map(|tag| tag_key(tag))
+        .collect::<HashSet<_>>();
+    let title_words = title_keywords(context.title);
+    let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+
+    let mut bump = |tag: &str, score: f64| {
+        let Some(parsed) = Tag::parse(tag) else {
+            return;
+        };
+        if parsed.is_private() && !context.include_private {
+            return;
+        }
+        let candidate = candidates.entry(parsed.key()).or_insert_with(|| Candidate {
+            tag: tag.to_string(),
+            score: 0.0,
+        });
+        candidate.score += score;
+    };
+
+    for user_tag in user_tags {
+        bump(
+            &user_tag.tag,
+            WEIGHT_COUNT * (1.0 + user_tag.count.max(0) as f64).ln(),
+        );
+    }
+
+    for entry in history {
+        let recency = recency_factor(context.now, entry.created_at);
+        let shares_entered = entry
+            .tags
+            .iter()
+            .filter_map(|tag| tag_key(tag))
+            .any(|key| entered.contains(&key));
+        let same_host = context.host.is_some_and(|host| host == entry.host);
+
+        for tag in &entry.tags {
+            let mut score = WEIGHT_RECENCY * recency;
+            if shares_entered {
+                score += WEIGHT_CO_OCCURRENCE * recency;
+            }
+            if same_host {
+                score += WEIGHT_SAME_HOST * recency;
+            }
+            bump(tag, score);
+        }
+    }
+
+    if let Some(suggestions) = pinboard {
+        for tag in &suggestions.recommended {
+            bump(tag, WEIGHT_PINBOARD_RECOMMENDED);
+        }
+        for tag in &suggestions.popular {
+            bump(tag, WEIGHT_PINBOARD_POPULAR);
+        }
+    }
+
+    let mut ranked = candidates
+        .into_iter()
+        .filter(|(key, _)| !entered.contains(key))
+        .map(|(_, candidate)| {
+            let title_bonus = if matches_title(&candidate.tag, &title_words) {
+                WEIGHT_TITLE_MATCH
+            } else {
+                0.0
+            };
+            RankedTag {
+                tag: candidate.tag,
+                score: candidate.score + title_bonus,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.tag.to_lowercase().cmp(&b.tag.to_lowercase()))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+fn tag_key(tag: &str) -> Option<String> {
+    Tag::parse(tag).map(|parsed| parsed.key())
+}
+
+fn recency_factor(now: i64, created_at: i64) -> f64 {
+    let age_days = (now - created_at).max(0) as f64 / SECS_PER_DAY;
+    0.5_f64.powf(age_days / RECENCY_HALF_LIFE_DAYS)
+}
+
+fn title_keywords(title: &str) -> HashSet<String> {
+    title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn matches_title(tag: &str, title_words: &HashSet<String>) -> bool {
+    let Some(parsed) = Tag::parse(tag) else {
+        return false;
+    };
+    let name = parsed.name().to_lowercase();
+    title_words.contains(&name)
+        || name
+            .split(['-', '_', '.'])
+            .filter(|part| part.chars().count() >= 2)
+            .any(|part| title_words.contains(part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rank_tags, RankingContext};
+    use crate::domain::bookmark::{TagSuggestions, UserTag};
+    use crate::tagging::history::HistoryEntry;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 86_400;
+
+    fn user_tag(tag: &str, count: i64) -> UserTag {
+        UserTag {
+            tag: tag.to_string(),
+            count,
+        }
+    }
+
+    fn entry(host: &str, tags: &[&str], age_days: i64) -> HistoryEntry {
+        HistoryEntry {
+            account: "default".to_string(),
+            host: host.to_string(),
+            tags: tags.iter().map(ToString::to_string).collect(),
+            created_at: NOW - age_days * DAY,
+        }
+    }
+
+    fn names(
+        context: &RankingContext<'_>,
+        user: &[UserTag],
+        history: &[HistoryEntry],
+    ) -> Vec<String> {
+        rank_tags(context, user, history, None, 10)
+            .into_iter()
+            .map(|ranked| ranked.tag)
+            .collect()
+    }
+
+    #[test]
+    fn popular_tags_rank_by_count_then_name() {
+        let context = RankingContext {
+            entered: &[],
+            host: None,
+            title: "",
+            now: NOW,
+            include_private: false,
+        };
+        let user = [user_tag("b", 5), user_tag("a", 5), user_tag("c", 50)];
+        assert_eq!(names(&context, &user, &[]), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn co_occurrence_and_host_history_outrank_raw_counts() {
+        let entered = ["rust".to_string()];
+        let context = RankingContext {
+            entered: &entered,
+            host: Some("docs.rs"),
+            title: "",
+            now: NOW,
+            include_private: false,
+        };
+        let user = [
+            user_tag("news", 400),
+            user_tag("rust", 90),
+            user_tag("async", 10),
+            user_tag("docs", 3),
+        ];
+        let history = [
+            entry("github.com", &["rust", "async"], 1),
+            entry("docs.rs", &["docs"], 2),
+        ];
+
+        let ranked = names(&context, &user, &history);
+        assert_eq!(ranked, vec!["async", "docs", "news"]);
+    }
+
+    #[test]
+    fn title_keywords_and_recency_break_ties() {
+        let context = RankingContext {
+            entered: &[],
+            host: None,
+            title: "Understanding Tokio internals",
+            now: NOW,
+            include_private: false,
+        };
+        let user = [
+            user_tag("tokio", 1),
+            user_tag("old", 1),
+            user_tag("fresh", 1),
+        ];
+        let history = [entry("a.com", &["old"], 365), entry("b.com", &["fresh"], 0)];
+
+        assert_eq!(
+            names(&context, &user, &history),
+            vec!["tokio", "fresh", "old"]
+        );
+    }
+
+    #[test]
+    fn pinboard_suggestions_are_merged_case_insensitively() {
+        let entered = ["Linux".to_string()];
+        let context = RankingContext {
+            entered: &entered,
+            host: None,
+            title: "",
+            now: NOW,
+            include_private: false,
+        };
+        let suggestions = TagSuggestions {
+            popular: vec!["linux".to_string(), "arch".to_string()],
+            recommended: vec!["Omarchy".to_string()],
+        };
+        let ranked = rank_tags(
+            &context,
+            &[user_tag("omarchy", 2)],
+            &[],
+            Some(&suggestions),
+            10,
+        )
+        .into_iter()
+        .map(|ranked| ranked.tag)
+        .collect::<Vec<_>>();
+        assert_eq!(ranked, vec!["omarchy", "arch"]);
+    }
+
+    #[test]
+    fn private_tags_are_only_suggested_when_asked_for() {
+        let mut context = RankingContext {
+            entered: &[],
+            host: None,
+            title: "",
+            now: NOW,
+            include_private: false,
+        };
+        let user = [user_tag(".finance", 9), user_tag("rust", 3)];
+        let history = [entry("example.com", &[".todo", "async"], 0)];
+        assert_eq!(names(&context, &user, &history), vec!["async", "rust"]);
+
+        context.include_private = true;
+        assert_eq!(
+            names(&context, &user, &history),
+            vec![".finance", ".todo", "async", "rust"]
+        );
+    }
+}
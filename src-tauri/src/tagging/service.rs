@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter};
+
+use crate::domain::bookmark::UserTag;
+use crate::security::accounts::{active_credentials, Credentials};
+use crate::AppState;
+
+const TAG_CACHE_MAX_AGE_SECS: i64 = 60 * 60;
+
+/// Serves the cached tag list immediately. An empty cache is filled inline;
+/// a stale one is refreshed in the background and announced with
+/// `tags:refreshed`.
+pub async fn cached_user_tags(
+    app: &AppHandle,
+    state: &Arc<AppState>,
+) -> Result<Vec<UserTag>, String> {
+    let cached = state.tag_cache.list().map_err(|e| e.to_string())?;
+    let never_fetched = state
+        .tag_cache
+        .fetched_at()
+        .map_err(|e| e.to_string())?
+        .is_none();
+
+    if cached.is_empty() && never_fetched {
+        return match refresh_user_tags(state).await {
+            Ok(tags) => Ok(tags),
+            Err(_) => Ok(cached),
+        };
+    }
+
+    if state
+        .tag_cache
+        .is_stale(TAG_CACHE_MAX_AGE_SECS)
+        .map_err(|e| e.to_string())?
+    {
+        let app = app.clone();
+        let state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Ok(tags) = refresh_user_tags(&state).await {
+                let _ = app.emit("tags:refreshed", tags.len());
+            }
+        });
+    }
+
+    Ok(cached)
+}
+
+pub async fn refresh_user_tags(state: &Arc<AppState>) -> Result<Vec<UserTag>, String> {
+    if !state.tag_cache.begin_refresh() {
+        return Err("tag refresh already running".to_string());
+    }
+
+    let result = fetch_and_store(state).await;
+    state.tag_cache.end_refresh();
+    result
+}
+
+async fn fetch_and_store(state: &Arc<AppState>) -> Result<Vec<UserTag>, String> {
+    let Credentials { token, client, .. } = active_credentials(state)?;
+
+    let tags = client
+        .get_user_tags(&token)
+        .await
+        .map_err(|e| e.to_string())?;
+    state
+        .tag_cache
+        .replace_all(&tags)
+        .map_err(|e| e.to_string())?;
+    Ok(tags)
+}
@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+
+use rusqlite::{params, OptionalExtension};
+
+use crate::domain::bookmark::ExistingBookmark;
+use crate::infra::db::{database_path, now_unix, open_db};
+
+/// How long after a submission it can still be undone.
+pub const UNDO_WINDOW_SECS: i64 = 10 * 60;
+
+// Snapshots that were never submitted are dropped after a day.
+const SNAPSHOT_MAX_AGE_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UndoStoreError {
+    #[error("db error: {0}")]
+    Db(String),
+    #[error("serialization error: {0}")]
+    Serde(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    /// The account the submission was saved to; undo replays against it.
+    pub account: String,
+    pub url: String,
+    /// The bookmark as it was before the submission; `None` if it was new.
+    pub previous: Option<ExistingBookmark>,
+    pub submitted_at: i64,
+}
+
+/// Remembers what `check_duplicate` saw for each URL so the submission that
+/// follows can be reverted.
+pub struct UndoStore {
+    db_path: PathBuf,
+}
+
+impl UndoStore {
+    pub fn new(custom_path: &str) -> Result<Self, UndoStoreError> {
+        let db_path = database_path(custom_path);
+        open_db(&db_path).map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        Ok(Self { db_path })
+    }
+
+    /// Records the pre-submission state of `url` on `account`. A snapshot
+    /// whose submission is still undoable is kept, so re-checking the URL
+    /// right after saving does not overwrite what undo restores.
+    pub fn capture(
+        &self,
+        account: &str,
+        url: &str,
+        previous: Option<&ExistingBookmark>,
+    ) -> Result<(), UndoStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        let now = now_unix();
+        let previous_json = previous
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| UndoStoreError::Serde(e.to_string()))?;
+
+        conn.execute(
+            "DELETE FROM undo_entries
+             WHERE (submitted_at IS NULL AND captured_at < ?1) OR submitted_at < ?2",
+            params![now - SNAPSHOT_MAX_AGE_SECS, now - UNDO_WINDOW_SECS],
+        )
+        .map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        conn.execute(
+            "INSERT INTO undo_entries(url, account, previous_json, captured_at, submitted_at)
+             VALUES(?1, ?4, ?2, ?3, NULL)
+             ON CONFLICT(url) DO UPDATE SET
+                account = excluded.account,
+                previous_json = excluded.previous_json,
+                captured_at = excluded.captured_at,
+                submitted_at = NULL
+             WHERE undo_entries.submitted_at IS NULL",
+            params![url, previous_json, now, account],
+        )
+        .map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Marks the snapshot captured for `url` on `account` as submitted.
+    /// Without a prior capture on that account there is nothing to restore,
+    /// so nothing becomes undoable.
+    pub fn mark_submitted(&self, account: &str, url: &str) -> Result<(), UndoStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        conn.execute(
+            "UPDATE undo_entries SET submitted_at = ?2 WHERE url = ?1 AND account = ?3",
+            params![url, now_unix(), account],
+        )
+        .map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Most recent submission still inside the undo window.
+    pub fn last_submission(&self) -> Result<Option<UndoEntry>, UndoStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        let row = conn
+            .query_row(
+                "SELECT url, previous_json, submitted_at, account FROM undo_entries
+                 WHERE submitted_at >= ?1
+                 ORDER BY submitted_at DESC, rowid DESC
+                 LIMIT 1",
+                params![now_unix() - UNDO_WINDOW_SECS],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .optional()
+            .map_err(|e| UndoStoreError::Db(e.to_string()))?;
+
+        let Some((url, previous_json, submitted_at, account)) = row else {
+            return Ok(None);
+        };
+        let previous = previous_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| UndoStoreError::Serde(e.to_string()))?;
+
+        Ok(Some(UndoEntry {
+            account,
+            url,
+            previous,
+            submitted_at,
+        }))
+    }
+
+    pub fn remove(&self, url: &str) -> Result<(), UndoStoreError> {
+        let conn = open_db(&self.db_path).map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        conn.execute("DELETE FROM undo_entries WHERE url = ?1", params![url])
+            .map_err(|e| UndoStoreError::Db(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoStore;
+    use crate::domain::bookmark::ExistingBookmark;
+    use crate::infra::db::TempDb;
+
+    #[test]
+    fn only_captured_and_submitted_urls_are_undoable() {
+        let db = TempDb::new("undo");
+        let store = UndoStore::new(db.path().to_str().unwrap()).unwrap();
+        let previous = ExistingBookmark {
+            url: "https://example.com/".to_string(),
+            title: "Before".to_string(),
+            notes: String::new(),
+            tags: vec!["old".to_string()],
+            private: true,
+            read_later: false,
+            time: "2024-01-01T00:00:00Z".to_string(),
+            meta: String::new(),
+        };
+
+        store
+            .capture("default", "https://new.example/", None)
+            .unwrap();
+        store
+            .capture("work", "https://example.com/", Some(&previous))
+            .unwrap();
+        assert!(store.last_submission().unwrap().is_none());
+
+        store
+            .mark_submitted("work", "https://never-checked.example/")
+            .unwrap();
+        assert!(store.last_submission().unwrap().is_none());
+
+        // A save of the same URL on another account is not this snapshot's.
+        store
+            .mark_submitted("default", "https://example.com/")
+            .unwrap();
+        assert!(store.last_submission().unwrap().is_none());
+
+        store
+            .mark_submitted("work", "https://example.com/")
+            .unwrap();
+        // Re-checking after the save must not replace the undo snapshot.
+        let mut after = previous.clone();
+        after.title = "After".to_string();
+        store
+            .capture("work", "https://example.com/", Some(&after))
+            .unwrap();
+
+        let entry = store.last_submission().unwrap().unwrap();
+        assert_eq!(entry.url, "https://example.com/");
+        assert_eq!(entry.account, "work");
+        assert_eq!(entry.previous.unwrap().title, "Before");
+
+        store.remove("https://example.com/").unwrap();
+        assert!(store.last_submission().unwrap().is_none());
+    }
+}
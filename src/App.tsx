@@ -3,7 +3,7 @@ import { getCurrentWindow } from "@tauri-apps/api/window";
 import { listen } from "@tauri-apps/api/event";
 import "./App.css";
 import { QuickAddForm } from "./features/quick-add/QuickAddForm";
-import { getOmarchyTheme, OmarchyTheme } from "./lib/tauri";
+import { AuthInvalid, getOmarchyTheme, ImportJob, OmarchyTheme } from "./lib/tauri";
 import { useBookmarkStore } from "./state/useBookmarkStore";
 
 const THEME_POLL_INTERVAL_MS = 2500;
@@ -162,7 +162,7 @@ const makeThemeSignature = (theme: OmarchyTheme) =>
     .join("|")}`;
 
 function App() {
-  const { hydrate, loading, refreshQueue } = useBookmarkStore();
+  const { hydrate, loading, refreshQueue, setStatusMessage, setTokenConfigured } = useBookmarkStore();
 
   useEffect(() => {
     let cancelled = false;
@@ -249,9 +249,51 @@ function App() {
         }
       });
 
+      const unlistenConflict = await listen("queue:item_conflict", () => {
+        if (!cancelled) {
+          setStatusMessage("A queued update was not sent: the bookmark changed on Pinboard meanwhile.");
+          void refreshQueue();
+        }
+      });
+
+      const unlistenAuth = await listen<AuthInvalid>("auth:token_invalid", (event) => {
+        if (!cancelled) {
+          const { account, message } = event.payload;
+          if (account === useBookmarkStore.getState().activeAccount) {
+            setTokenConfigured(false);
+          }
+          setStatusMessage(
+            `${message}. Queued items for account ${account} are paused until you save a new token.`,
+          );
+          void refreshQueue();
+        }
+      });
+
+      const unlistenImportProgress = await listen<ImportJob>("import:progress", (event) => {
+        if (!cancelled) {
+          const job = event.payload;
+          setStatusMessage(`Importing ${job.source}: ${job.processed}/${job.total}`);
+        }
+      });
+      const unlistenImportFinished = await listen<ImportJob>("import:finished", (event) => {
+        if (!cancelled) {
+          const { source, status, counts, error } = event.payload;
+          setStatusMessage(
+            status === "done"
+              ? `Imported ${source}: ${counts.queued} queued, ${counts.duplicates} duplicates, ${counts.invalid} skipped`
+              : `Import of ${source} stopped: ${error ?? "unknown error"}`,
+          );
+          void refreshQueue();
+        }
+      });
+
       return () => {
         unlistenSent();
         unlistenFailed();
+        unlistenConflict();
+        unlistenAuth();
+        unlistenImportProgress();
+        unlistenImportFinished();
       };
     };
 
@@ -264,7 +306,7 @@ function App() {
       cancelled = true;
       cleanup?.();
     };
-  }, [refreshQueue]);
+  }, [refreshQueue, setStatusMessage, setTokenConfigured]);
 
   if (loading) {
     return <main className="app-shell loading">Booting ommapin...</main>;
@@ -1,4 +1,17 @@
-import { QueueItem } from "../../lib/tauri";
+import { QueueItem, QueueOperation } from "../../lib/tauri";
+
+const describeOperation = (operation: QueueOperation) => {
+  switch (operation.kind) {
+    case "addBookmark":
+      return operation.payload.title || operation.payload.url;
+    case "deleteBookmark":
+      return `delete ${operation.url}`;
+    case "renameTag":
+      return `rename tag ${operation.from} -> ${operation.to}`;
+    case "deleteTag":
+      return `delete tag ${operation.tag}`;
+  }
+};
 
 interface QueueStatusProps {
   queue: QueueItem[];
@@ -22,7 +35,7 @@ export function QueueStatus({ queue, onRetry }: QueueStatusProps) {
         {queue.slice(0, 5).map((item, index) => (
           <li key={item.id}>
             <span className="queue-item-title">
-              [{String(index + 1).padStart(2, "0")}] {item.payload.title || item.payload.url}
+              [{String(index + 1).padStart(2, "0")}] {describeOperation(item.operation)}
             </span>
             <span className="queue-item-meta">
               attempts {item.attemptCount}
@@ -3,21 +3,41 @@ import { useEffect, useRef, useState } from "react";
 import { useForm } from "react-hook-form";
 import { z } from "zod";
 import {
+  AccountList,
+  AuthStyle,
+  BookmarkConflict,
   BookmarkPayload,
+  CredentialSettings,
+  CredentialStatus,
   ExistingBookmark,
   SubmitIntent,
+  TagPreview,
+  UndoInfo,
   checkDuplicate,
   clearToken,
+  deleteBookmark,
+  evaluateDomainRules,
   fetchTagSuggestions,
   fetchUrlTitle,
   fetchUserTags,
+  getCredentialSettings,
+  getUndoStatus,
+  listAccounts,
+  previewTagNormalization,
+  rankTagSuggestions,
+  saveAccountBackend,
+  saveCredentialSettings,
   saveToken,
   submitBookmark,
+  switchAccount,
+  undoLastSubmission,
+  unlockCredentials,
 } from "../../lib/tauri";
 import { TagSuggestions } from "../tags/TagSuggestions";
 import { useBookmarkStore } from "../../state/useBookmarkStore";
 import { readText } from "@tauri-apps/plugin-clipboard-manager";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { listen } from "@tauri-apps/api/event";
 
 const startsLikeUrl = (value: string) => /^https?:\/\//.test(value) || value.includes(".");
 
@@ -57,6 +77,13 @@ type FormValues = z.infer<typeof schema>;
 export function QuickAddForm() {
   const [intent, setIntent] = useState<SubmitIntent>("create");
   const [tokenInput, setTokenInput] = useState("");
+  const [accounts, setAccounts] = useState<AccountList | null>(null);
+  const [newAccountName, setNewAccountName] = useState("");
+  const [backendUrl, setBackendUrl] = useState("");
+  const [backendAuth, setBackendAuth] = useState<AuthStyle>("queryToken");
+  const [backendInterval, setBackendInterval] = useState("3");
+  const [credentialStatus, setCredentialStatus] = useState<CredentialStatus | null>(null);
+  const [passphrase, setPassphrase] = useState("");
   const [existingTags, setExistingTags] = useState<string[]>([]);
   const [existingTagsLoaded, setExistingTagsLoaded] = useState(false);
   const [showTokenEditor, setShowTokenEditor] = useState(false);
@@ -65,6 +92,13 @@ export function QuickAddForm() {
   const [tagsInputFocused, setTagsInputFocused] = useState(false);
   const [autocompleteDismissed, setAutocompleteDismissed] = useState(false);
   const [activeAutocompleteIndex, setActiveAutocompleteIndex] = useState(0);
+  const [tagPreview, setTagPreview] = useState<TagPreview | null>(null);
+  const [rankedTags, setRankedTags] = useState<string[]>([]);
+  const [confirmingDelete, setConfirmingDelete] = useState(false);
+  const [undoInfo, setUndoInfo] = useState<UndoInfo | null>(null);
+  const [existingTime, setExistingTime] = useState<string | null>(null);
+  const [baseBookmark, setBaseBookmark] = useState<ExistingBookmark | null>(null);
+  const [conflict, setConflict] = useState<BookmarkConflict | null>(null);
   const inspectRequestRef = useRef(0);
   const lastInspectedUrlRef = useRef("");
   const focusTitleAfterInspectRef = useRef(false);
@@ -77,7 +111,9 @@ export function QuickAddForm() {
     setSuggestions,
     setStatusMessage,
     setTokenConfigured,
+    setActiveAccount,
     refreshQueue,
+    hydrate,
   } = useBookmarkStore();
 
   const {
@@ -86,6 +122,7 @@ export function QuickAddForm() {
     reset,
     setValue,
     getValues,
+    setError,
     watch,
     formState: { errors },
   } = useForm<FormValues>({
@@ -119,7 +156,12 @@ export function QuickAddForm() {
       readLater: false,
     });
     setIntent("create");
+    setExistingTime(null);
+    setBaseBookmark(null);
+    setConflict(null);
+    setConfirmingDelete(false);
     setSuggestions(undefined);
+    setRankedTags([]);
     lastInspectedUrlRef.current = "";
   };
 
@@ -130,6 +172,9 @@ export function QuickAddForm() {
     setValue("tags", bookmark.tags.join(" "), { shouldDirty: true });
     setValue("private", bookmark.private, { shouldDirty: true });
     setValue("readLater", bookmark.readLater, { shouldDirty: true });
+    setExistingTime(bookmark.time || null);
+    setBaseBookmark(bookmark);
+    setConflict(null);
     setIntent("update");
   };
 
@@ -149,7 +194,12 @@ export function QuickAddForm() {
 
     const suggestionPool = Array.from(
       new Map(
-        [...(suggestions?.recommended ?? []), ...(suggestions?.popular ?? []), ...existingTags].map((tag) => [
+        [
+          ...rankedTags,
+          ...(suggestions?.recommended ?? []),
+          ...(suggestions?.popular ?? []),
+          ...existingTags,
+        ].map((tag) => [
           tag.toLowerCase(),
           tag,
         ]),
@@ -202,7 +252,22 @@ export function QuickAddForm() {
     const tagsStartedAt = performance.now();
     const titleStartedAt = performance.now();
 
+    // Replaces `evaluatedTitle` with a rule's rewrite unless the title has
+    // changed since, e.g. by typing or loading an existing bookmark.
+    const applyTitleRewrite = (evaluatedTitle: string, rewritten?: string | null) => {
+      if (
+        requestId !== inspectRequestRef.current ||
+        !evaluatedTitle ||
+        !rewritten ||
+        getValues("title").trim() !== evaluatedTitle
+      ) {
+        return;
+      }
+      setValue("title", rewritten, { shouldDirty: true });
+    };
+
     const titlePromise = fetchUrlTitle(url);
+    const rulesPromise = evaluateDomainRules(url, titleBeforeInspect);
     const dedupePromise = tokenConfigured ? checkDuplicate(url) : Promise.resolve(undefined);
     const tagsPromise = tokenConfigured ? fetchTagSuggestions(url) : Promise.resolve(undefined);
 
@@ -232,6 +297,25 @@ export function QuickAddForm() {
         setStatusMessage(`Could not check duplicate URL yet: ${String(error)}`);
       });
 
+    void rulesPromise
+      .then((outcome) => {
+        if (requestId !== inspectRequestRef.current || !outcome.matchedRules.length) {
+          return;
+        }
+
+        outcome.addTags.forEach((tag) => appendTag(tag));
+        if (outcome.private) {
+          setValue("private", true, { shouldDirty: true });
+        }
+        if (outcome.readLater) {
+          setValue("readLater", true, { shouldDirty: true });
+        }
+        applyTitleRewrite(titleBeforeInspect, outcome.title);
+      })
+      .catch(() => {
+        // rules are re-applied on submit, so a failed preview is not fatal
+      });
+
     void tagsPromise
       .then((tagsResult) => {
         logInspectTiming(
@@ -247,6 +331,7 @@ export function QuickAddForm() {
         }
 
         setSuggestions(tagsResult);
+        void refreshRankedTags(url, tagsResult);
       })
       .catch((error) => {
         logInspectTiming("tags failed", tagsStartedAt);
@@ -278,6 +363,12 @@ export function QuickAddForm() {
         const fetchedTitle = fetchedTitleRaw?.trim();
         if (fetchedTitle && !titleBeforeInspect) {
           setValue("title", fetchedTitle, { shouldDirty: true });
+          // the rules saw an empty title; show the rewrite submit will apply
+          void evaluateDomainRules(url, fetchedTitle)
+            .then((outcome) => applyTitleRewrite(fetchedTitle, outcome.title))
+            .catch(() => {
+              // rules are re-applied on submit, so a failed preview is not fatal
+            });
         }
       })
       .catch(() => {
@@ -286,6 +377,20 @@ export function QuickAddForm() {
       });
   };
 
+  const refreshRankedTags = async (url: string, urlSuggestions = suggestions) => {
+    if (!tokenConfigured || !url) {
+      setRankedTags([]);
+      return;
+    }
+
+    try {
+      const ranked = await rankTagSuggestions(url, getValues("title"), getCurrentTags(), urlSuggestions);
+      setRankedTags(ranked.map((item) => item.tag));
+    } catch {
+      // keep non-blocking; autocomplete falls back to Pinboard suggestions
+    }
+  };
+
   const tryPrefillFromClipboard = async () => {
     if (getValues("url").trim() || clipboardPrefillInFlightRef.current) {
       return;
@@ -334,6 +439,54 @@ export function QuickAddForm() {
     void loadExistingTags();
   }, [existingTagsLoaded, tokenConfigured]);
 
+  useEffect(() => {
+    let disposed = false;
+    let unlisten: (() => void) | undefined;
+
+    void listen("tags:refreshed", () => {
+      setExistingTagsLoaded(false);
+    }).then((dispose) => {
+      if (disposed) {
+        dispose();
+      } else {
+        unlisten = dispose;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, []);
+
+  const refreshUndoStatus = () => {
+    getUndoStatus()
+      .then(setUndoInfo)
+      .catch(() => setUndoInfo(null));
+  };
+
+  useEffect(() => {
+    refreshUndoStatus();
+    window.addEventListener("focus", refreshUndoStatus);
+    return () => {
+      window.removeEventListener("focus", refreshUndoStatus);
+    };
+  }, []);
+
+  const onUndo = async () => {
+    try {
+      const result = await undoLastSubmission();
+      setStatusMessage(result.message);
+      if (result.queued) {
+        await refreshQueue();
+      }
+    } catch (error) {
+      setStatusMessage(`Undo failed: ${String(error)}`);
+    } finally {
+      refreshUndoStatus();
+    }
+  };
+
   useEffect(() => {
     const onFocus = () => {
       void tryPrefillFromClipboard();
@@ -463,6 +616,35 @@ export function QuickAddForm() {
     setAutocompleteDismissed(false);
   }, [tagsInputValue]);
 
+  useEffect(() => {
+    if (/\s$/.test(tagsInputValue ?? "") && lastInspectedUrlRef.current) {
+      void refreshRankedTags(lastInspectedUrlRef.current);
+    }
+  }, [tagsInputValue]);
+
+  useEffect(() => {
+    const rawTags = (tagsInputValue ?? "").split(/\s+/).filter(Boolean);
+    if (!rawTags.length) {
+      setTagPreview(null);
+      return;
+    }
+
+    let cancelled = false;
+    previewTagNormalization(rawTags)
+      .then((preview) => {
+        if (!cancelled) {
+          setTagPreview(preview);
+        }
+      })
+      .catch(() => {
+        // preview is informational; submit normalizes regardless
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [tagsInputValue]);
+
   useEffect(() => {
     if (activeAutocompleteIndex < tagAutocompleteOptions.length) {
       return;
@@ -481,10 +663,30 @@ export function QuickAddForm() {
         private: values.private,
         readLater: values.readLater,
         intent,
+        // keep the bookmark's original date when rewriting it
+        createdAt: intent === "create" ? null : existingTime,
+        // lets the backend refuse to overwrite edits made elsewhere
+        base: intent === "update" ? baseBookmark : null,
       };
 
       const result = await submitBookmark(payload);
 
+      if (result.status === "conflict" && result.conflict) {
+        setConflict(result.conflict);
+        setStatusMessage(result.message);
+        return;
+      }
+
+      if (result.fieldErrors.length) {
+        result.fieldErrors.forEach((fieldError) => {
+          if (fieldError.field !== "createdAt") {
+            setError(fieldError.field, { type: "server", message: fieldError.message });
+          }
+        });
+        setStatusMessage(result.message);
+        return;
+      }
+
       if (!result.queued) {
         resetBookmarkForm();
         try {
@@ -504,19 +706,76 @@ export function QuickAddForm() {
     }
   };
 
+  const keepLocalVersion = () => {
+    if (!conflict) {
+      return;
+    }
+    // the next write only overwrites the version just reviewed
+    setBaseBookmark(conflict.remote ?? null);
+    setConflict(null);
+    setStatusMessage("Write again to overwrite the Pinboard version.");
+  };
+
+  const loadRemoteVersion = () => {
+    if (!conflict) {
+      return;
+    }
+    if (conflict.remote) {
+      applyExistingBookmark(conflict.remote, conflict.url);
+      setStatusMessage("Loaded the current Pinboard version.");
+    } else {
+      setIntent("create");
+      setExistingTime(null);
+      setBaseBookmark(null);
+      setConflict(null);
+      setStatusMessage("Bookmark was deleted on Pinboard; writing will create it again.");
+    }
+  };
+
+  const onDelete = async () => {
+    const url = getValues("url");
+    if (!confirmingDelete) {
+      setConfirmingDelete(true);
+      setStatusMessage("Press delete again to remove this bookmark from Pinboard.");
+      return;
+    }
+
+    setSubmitting(true);
+    try {
+      const result = await deleteBookmark(url, true);
+      resetBookmarkForm();
+      setStatusMessage(result.message);
+      if (result.queued) {
+        await refreshQueue();
+      }
+    } catch (error) {
+      setConfirmingDelete(false);
+      setStatusMessage(`Delete failed: ${String(error)}`);
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
   const persistToken = async () => {
     if (!tokenInput.trim()) {
       setStatusMessage("Enter your Pinboard token in username:TOKEN format.");
       return;
     }
 
+    const accountName = newAccountName.trim();
     try {
-      await saveToken(tokenInput.trim());
+      const username = await saveToken(tokenInput.trim(), accountName || undefined);
+      setTokenInput("");
+      setNewAccountName("");
+      if (accountName) {
+        setAccounts(await listAccounts());
+        setStatusMessage(`Token for ${username} verified and saved as account ${accountName}.`);
+        return;
+      }
       setTokenConfigured(true);
       setExistingTagsLoaded(false);
-      setTokenInput("");
       setShowTokenEditor(false);
-      setStatusMessage("Token saved in system keyring.");
+      setStatusMessage(`Token for ${username} verified and saved in system keyring.`);
     } catch (error) {
       setStatusMessage(`Failed to save token: ${String(error)}`);
     }
@@ -525,7 +784,12 @@ export function QuickAddForm() {
   const removeToken = async () => {
     try {
       await clearToken();
-      setTokenConfigured(false);
+      const list = await listAccounts();
+      setAccounts(list);
+      setActiveAccount(list.active);
+      setTokenConfigured(
+        list.accounts.some((account) => account.name === list.active && account.tokenConfigured),
+      );
       resetBookmarkForm();
       setStatusMessage("Pinboard token cleared.");
     } catch (error) {
@@ -533,9 +797,101 @@ export function QuickAddForm() {
     }
   };
 
+  const selectAccount = async (name: string) => {
+    try {
+      const list = await switchAccount(name);
+      setAccounts(list);
+      setActiveAccount(list.active);
+      setTokenConfigured(
+        list.accounts.some((account) => account.name === list.active && account.tokenConfigured),
+      );
+      setExistingTagsLoaded(false);
+      resetBookmarkForm();
+      setStatusMessage(`Switched to account ${name}.`);
+    } catch (error) {
+      setStatusMessage(`Failed to switch account: ${String(error)}`);
+    }
+  };
+
+  const persistBackend = async () => {
+    const accountName = newAccountName.trim() || accounts?.active || "default";
+    const baseUrl = backendUrl.trim();
+    try {
+      const list = await saveAccountBackend(
+        accountName,
+        baseUrl
+          ? {
+              baseUrl,
+              authStyle: backendAuth,
+              writeIntervalSecs: Math.max(0, Math.round(Number(backendInterval) || 0)),
+            }
+          : null,
+      );
+      setAccounts(list);
+      setExistingTagsLoaded(false);
+      setStatusMessage(
+        baseUrl
+          ? `Account ${accountName} now uses ${baseUrl}. Save its token to verify it.`
+          : `Account ${accountName} uses Pinboard again.`,
+      );
+    } catch (error) {
+      setStatusMessage(`Failed to save API server: ${String(error)}`);
+    }
+  };
+
   const shouldShowTokenPanel = !tokenConfigured || showTokenEditor;
   const formLocked = submitting;
 
+  useEffect(() => {
+    if (!shouldShowTokenPanel) {
+      return;
+    }
+    listAccounts()
+      .then(setAccounts)
+      .catch(() => setAccounts(null));
+    getCredentialSettings()
+      .then(setCredentialStatus)
+      .catch(() => setCredentialStatus(null));
+  }, [shouldShowTokenPanel]);
+
+  useEffect(() => {
+    const backend = accounts?.accounts.find(
+      (account) => account.name === accounts.active,
+    )?.backend;
+    setBackendUrl(backend?.baseUrl ?? "");
+    setBackendAuth(backend?.authStyle ?? "queryToken");
+    setBackendInterval(String(backend?.writeIntervalSecs ?? 3));
+  }, [accounts]);
+
+  const updateCredentialSettings = async (changes: Partial<CredentialSettings>) => {
+    if (!credentialStatus) {
+      return;
+    }
+    try {
+      setCredentialStatus(
+        await saveCredentialSettings({ ...credentialStatus.settings, ...changes }),
+      );
+      await hydrate();
+    } catch (error) {
+      setStatusMessage(`Failed to change token storage: ${String(error)}`);
+    }
+  };
+
+  const unlockCredentialFile = async () => {
+    try {
+      setCredentialStatus(await unlockCredentials(passphrase));
+      setPassphrase("");
+      setAccounts(await listAccounts());
+      await hydrate();
+      setStatusMessage("Credential file unlocked.");
+    } catch (error) {
+      setStatusMessage(`Failed to unlock credential file: ${String(error)}`);
+    }
+  };
+
+  const fileLocked =
+    credentialStatus?.settings.backend === "file" && !credentialStatus.fileUnlocked;
+
   return (
     <main className="app-shell">
       <header className="app-header">
@@ -572,12 +928,135 @@ export function QuickAddForm() {
               ? "Update your token or log out. Credentials stay in your desktop keyring (Secret Service)."
               : "Token is required before quick add is enabled."}
           </p>
+          {credentialStatus ? (
+            <div className="token-storage">
+              <label className="token-label">
+                storage
+                <select
+                  value={credentialStatus.settings.backend}
+                  onChange={(event) =>
+                    void updateCredentialSettings({
+                      backend: event.target.value as CredentialSettings["backend"],
+                    })
+                  }
+                >
+                  <option value="keyring">system keyring</option>
+                  <option value="file">encrypted file</option>
+                </select>
+              </label>
+              {fileLocked ? (
+                <label className="token-label">
+                  {credentialStatus.fileExists ? "file passphrase" : "new file passphrase"}
+                  <input
+                    type="password"
+                    value={passphrase}
+                    onChange={(event) => setPassphrase(event.target.value)}
+                    onKeyDown={(event) => {
+                      if (event.key === "Enter") {
+                        event.preventDefault();
+                        void unlockCredentialFile();
+                      }
+                    }}
+                  />
+                </label>
+              ) : null}
+              {fileLocked ? (
+                <button type="button" onClick={() => void unlockCredentialFile()}>
+                  Unlock
+                </button>
+              ) : null}
+              <label className="toggle-option">
+                <input
+                  type="checkbox"
+                  checked={credentialStatus.settings.allowEnv}
+                  onChange={(event) => void updateCredentialSettings({ allowEnv: event.target.checked })}
+                />
+                <span>read OMMAPIN_PINBOARD_TOKEN from the environment first</span>
+              </label>
+              <label className="token-label">
+                pass entry
+                <input
+                  defaultValue={credentialStatus.settings.passEntry ?? ""}
+                  placeholder="pinboard/{account}"
+                  onBlur={(event) => {
+                    const passEntry = event.target.value.trim() || null;
+                    if (passEntry !== (credentialStatus.settings.passEntry ?? null)) {
+                      void updateCredentialSettings({ passEntry });
+                    }
+                  }}
+                />
+              </label>
+            </div>
+          ) : null}
+          {accounts && accounts.accounts.length > 1 ? (
+            <label className="token-label">
+              account
+              <select
+                value={accounts.active}
+                onChange={(event) => void selectAccount(event.target.value)}
+              >
+                {accounts.accounts.map((account) => (
+                  <option key={account.name} value={account.name}>
+                    {account.username ? `${account.name} (${account.username})` : account.name}
+                    {account.tokenConfigured ? "" : " - no token"}
+                  </option>
+                ))}
+              </select>
+            </label>
+          ) : null}
+          <label className="token-label">
+            add as account
+            <input
+              value={newAccountName}
+              onChange={(event) => setNewAccountName(event.target.value)}
+              placeholder="leave empty to update the current account"
+            />
+          </label>
+          <div className="token-storage">
+            <label className="token-label">
+              API server
+              <input
+                value={backendUrl}
+                onChange={(event) => setBackendUrl(event.target.value)}
+                placeholder="empty for api.pinboard.in"
+              />
+            </label>
+            {backendUrl.trim() ? (
+              <>
+                <label className="token-label">
+                  auth
+                  <select
+                    value={backendAuth}
+                    onChange={(event) => setBackendAuth(event.target.value as AuthStyle)}
+                  >
+                    <option value="queryToken">auth_token query parameter</option>
+                    <option value="bearer">bearer header</option>
+                    <option value="basic">basic auth (username:password)</option>
+                  </select>
+                </label>
+                <label className="token-label">
+                  seconds between writes
+                  <input
+                    type="number"
+                    min={0}
+                    value={backendInterval}
+                    onChange={(event) => setBackendInterval(event.target.value)}
+                  />
+                </label>
+              </>
+            ) : null}
+            <button type="button" onClick={() => void persistBackend()}>
+              Save server
+            </button>
+          </div>
           <label className="token-label">
             token
             <input
               value={tokenInput}
               onChange={(event) => setTokenInput(event.target.value)}
-              placeholder="username:TOKEN"
+              placeholder={
+                backendAuth === "bearer" && backendUrl.trim() ? "API token" : "username:TOKEN"
+              }
             />
           </label>
           <div className="token-actions">
@@ -624,6 +1103,7 @@ export function QuickAddForm() {
                   <label>
                     <span className="field-label">[notes]</span>
                     <textarea {...register("notes")} rows={4} placeholder="Optional notes" />
+                    {errors.notes ? <small>{errors.notes.message}</small> : null}
                   </label>
 
                   <label>
@@ -667,6 +1147,10 @@ export function QuickAddForm() {
                         </ul>
                       ) : null}
                     </div>
+                    {errors.tags ? <small>{errors.tags.message}</small> : null}
+                    {tagPreview?.rewrites.length ? (
+                      <small className="tag-preview">sends: {tagPreview.tags.join(" ")}</small>
+                    ) : null}
                   </label>
 
                   <TagSuggestions suggestions={suggestions} onAddTag={appendTag} onAddAll={addAllSuggested} />
@@ -686,13 +1170,71 @@ export function QuickAddForm() {
                     <button type="submit" className="button-primary" disabled={formLocked || !tokenConfigured}>
                       {submitting ? "Saving..." : "Write bookmark"}
                     </button>
-                    <span className="intent-pill">intent {intent === "update" ? ":: update" : ":: create"}</span>
+                    {intent !== "create" ? (
+                      <button type="button" disabled={formLocked} onClick={() => void onDelete()}>
+                        {confirmingDelete ? "Confirm delete" : "Delete"}
+                      </button>
+                    ) : null}
+                    {intent === "create" ? (
+                      <span className="intent-pill">intent :: create</span>
+                    ) : (
+                      <button
+                        type="button"
+                        className="intent-pill"
+                        title="Merge keeps existing tags, notes and date; update replaces them"
+                        onClick={() => setIntent(intent === "update" ? "merge" : "update")}
+                      >
+                        intent :: {intent}
+                      </button>
+                    )}
                   </div>
                 </fieldset>
               </form>
         </>
       ) : null}
 
+      {undoInfo ? (
+        <p className="status-message">
+          last save: {undoInfo.url}{" "}
+          <button type="button" onClick={() => void onUndo()}>
+            {undoInfo.action === "delete" ? "Undo (delete)" : "Undo (restore)"}
+          </button>
+        </p>
+      ) : null}
+
+      {conflict ? (
+        <div className="conflict-panel">
+          <table>
+            <thead>
+              <tr>
+                <th>field</th>
+                <th>opened</th>
+                <th>pinboard</th>
+                <th>yours</th>
+              </tr>
+            </thead>
+            <tbody>
+              {conflict.changes.map((change) => (
+                <tr key={change.field}>
+                  <td>{change.field}</td>
+                  <td>{change.base}</td>
+                  <td>{conflict.remote ? change.remote : "(deleted)"}</td>
+                  <td>{change.local}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+          <div className="submit-row">
+            <button type="button" onClick={keepLocalVersion}>
+              Keep mine
+            </button>
+            <button type="button" onClick={loadRemoteVersion}>
+              Load Pinboard version
+            </button>
+          </div>
+        </div>
+      ) : null}
+
       {statusMessage ? <p className="status-message">{statusMessage}</p> : null}
     </main>
   );
@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 
-export type SubmitIntent = "create" | "update";
+export type SubmitIntent = "create" | "update" | "merge";
 
 export interface BookmarkPayload {
   url: string;
@@ -10,6 +10,8 @@ export interface BookmarkPayload {
   private: boolean;
   readLater: boolean;
   intent: SubmitIntent;
+  createdAt?: string | null;
+  base?: ExistingBookmark | null;
 }
 
 export interface ExistingBookmark {
@@ -20,6 +22,47 @@ export interface ExistingBookmark {
   private: boolean;
   readLater: boolean;
   time: string;
+  meta?: string;
+}
+
+export interface FieldChange {
+  field: "title" | "notes" | "tags" | "private" | "readLater";
+  base: string;
+  remote: string;
+  local: string;
+}
+
+export interface BookmarkConflict {
+  url: string;
+  base: ExistingBookmark;
+  remote?: ExistingBookmark | null;
+  local: BookmarkPayload;
+  changes: FieldChange[];
+}
+
+export interface QueuedConflict {
+  id: number;
+  conflict: BookmarkConflict;
+  error?: string | null;
+}
+
+export interface DeleteBookmarkResult {
+  status: "confirm" | "sent" | "queued";
+  message: string;
+  queued: boolean;
+  bookmark?: ExistingBookmark | null;
+}
+
+export interface UndoInfo {
+  url: string;
+  action: "delete" | "restore";
+  expiresAt: number;
+}
+
+export interface BookmarkList {
+  bookmarks: ExistingBookmark[];
+  offline: boolean;
+  message?: string | null;
 }
 
 export interface DuplicateCheckResult {
@@ -35,40 +78,217 @@ export interface TagSuggestions {
 export interface QueueStats {
   pending: number;
   failed: number;
+  conflicts: number;
+  paused: boolean;
+  pausedAccounts: string[];
 }
 
 export interface SessionInfo {
   tokenConfigured: boolean;
+  credentialsLocked: boolean;
+  activeAccount: string;
   queueStats: QueueStats;
 }
 
+export type AuthStyle = "queryToken" | "bearer" | "basic";
+
+export interface AccountBackend {
+  baseUrl: string;
+  authStyle: AuthStyle;
+  writeIntervalSecs: number;
+}
+
+export interface AccountInfo {
+  name: string;
+  username: string;
+  tokenConfigured: boolean;
+  backend: AccountBackend | null;
+}
+
+export interface AccountList {
+  active: string;
+  accounts: AccountInfo[];
+}
+
+export type CredentialBackend = "keyring" | "file";
+
+export interface CredentialSettings {
+  backend: CredentialBackend;
+  allowEnv: boolean;
+  passEntry?: string | null;
+}
+
+export interface CredentialStatus {
+  settings: CredentialSettings;
+  fileExists: boolean;
+  fileUnlocked: boolean;
+}
+
+export interface AuthInvalid {
+  account: string;
+  message: string;
+}
+
+export type BookmarkField = "title" | "notes" | "tags" | "createdAt";
+
+export interface FieldError {
+  field: BookmarkField;
+  message: string;
+}
+
 export interface SubmitResult {
   status: string;
   message: string;
   queued: boolean;
+  fieldErrors: FieldError[];
+  conflict?: BookmarkConflict;
 }
 
+export type QueueOperation =
+  | { kind: "addBookmark"; payload: BookmarkPayload }
+  | { kind: "deleteBookmark"; url: string }
+  | { kind: "renameTag"; from: string; to: string }
+  | { kind: "deleteTag"; tag: string };
+
 export interface QueueItem {
   id: number;
-  payload: BookmarkPayload;
+  account: string;
+  operation: QueueOperation;
   attemptCount: number;
   nextAttemptAt: number;
   lastError?: string;
 }
 
+export type ImportFormat = "netscape" | "csv" | "json" | "urlList";
+
+export interface ImportJob {
+  id: number;
+  account: string;
+  source: string;
+  format: ImportFormat;
+  status: "running" | "done" | "failed";
+  total: number;
+  processed: number;
+  counts: { queued: number; duplicates: number; invalid: number };
+  error?: string | null;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export type ExportFormat = "netscape" | "pinboardJson" | "csv" | "markdown";
+
+export type RestoreMode = "merge" | "replace";
+
+export type BackupSummary = {
+  queueItems: number;
+  settings: number;
+  tagHistory: number;
+  userTags: number;
+  tokens: string[];
+};
+
 export interface QueueRetryResult {
   sent: number;
   remaining: number;
 }
 
+export type PatternKind = "glob" | "regex";
+
+export interface TitleRewrite {
+  pattern: string;
+  replacement: string;
+}
+
+export interface DomainRule {
+  name: string;
+  kind: PatternKind;
+  host: string;
+  path?: string | null;
+  priority: number;
+  addTags: string[];
+  private: boolean;
+  readLater: boolean;
+  titleRewrite?: TitleRewrite | null;
+  enabled: boolean;
+}
+
+export interface RuleOutcome {
+  matchedRules: string[];
+  addTags: string[];
+  private: boolean;
+  readLater: boolean;
+  title?: string | null;
+}
+
+export type CaseFolding = "preserve" | "lowercase";
+
+export interface TagNormalization {
+  caseFolding: CaseFolding;
+  stripTrailingPunctuation: boolean;
+  splitOnCommas: boolean;
+  aliases: Record<string, string>;
+  privateTags: string[];
+}
+
+export interface TagRewrite {
+  from: string;
+  to: string;
+}
+
+export interface TagPreview {
+  tags: string[];
+  rewrites: TagRewrite[];
+}
+
+export interface TagBundle {
+  name: string;
+  tags: string[];
+}
+
+export interface BundleExpansion {
+  tags: string[];
+  expanded: TagRewrite[];
+  unknown: string[];
+}
+
+export interface RankedTag {
+  tag: string;
+  score: number;
+}
+
+export interface UserTag {
+  tag: string;
+  count: number;
+}
+
+export interface TagMutationResult {
+  status: string;
+  message: string;
+  queued: boolean;
+  affected: UserTag[];
+  affectedBookmarks: number;
+}
+
 export interface OmarchyTheme {
   name: string;
   colors: Record<string, string>;
 }
 
 export const initSession = () => invoke<SessionInfo>("init_session");
-export const saveToken = (token: string) => invoke<void>("save_token", { token });
-export const clearToken = () => invoke<void>("clear_token");
+export const saveToken = (token: string, account?: string) =>
+  invoke<string>("save_token", { token, account: account ?? null });
+export const clearToken = (account?: string) =>
+  invoke<void>("clear_token", { account: account ?? null });
+export const listAccounts = () => invoke<AccountList>("list_accounts");
+export const switchAccount = (name: string) =>
+  invoke<AccountList>("set_active_account", { name });
+export const saveAccountBackend = (account: string, backend: AccountBackend | null) =>
+  invoke<AccountList>("save_account_backend", { account, backend });
+export const getCredentialSettings = () => invoke<CredentialStatus>("get_credential_settings");
+export const saveCredentialSettings = (settings: CredentialSettings) =>
+  invoke<CredentialStatus>("save_credential_settings", { settings });
+export const unlockCredentials = (passphrase: string) =>
+  invoke<CredentialStatus>("unlock_credentials", { passphrase });
 export const checkDuplicate = (url: string) =>
   invoke<DuplicateCheckResult>("check_duplicate", { url });
 export const fetchTagSuggestions = (url: string) =>
@@ -80,3 +300,60 @@ export const submitBookmark = (payload: BookmarkPayload) =>
 export const getQueue = () => invoke<QueueItem[]>("queue_list");
 export const retryQueueNow = () => invoke<QueueRetryResult>("queue_retry_now");
 export const getOmarchyTheme = () => invoke<OmarchyTheme | null>("get_omarchy_theme");
+export const getDomainRules = () => invoke<DomainRule[]>("get_domain_rules");
+export const saveDomainRules = (rules: DomainRule[]) => invoke<void>("save_domain_rules", { rules });
+export const evaluateDomainRules = (url: string, title: string) =>
+  invoke<RuleOutcome>("evaluate_domain_rules", { url, title });
+export const getTagNormalization = () => invoke<TagNormalization>("get_tag_normalization");
+export const saveTagNormalization = (settings: TagNormalization) =>
+  invoke<void>("save_tag_normalization", { settings });
+export const previewTagNormalization = (tags: string[]) =>
+  invoke<TagPreview>("preview_tag_normalization", { tags });
+export const rankTagSuggestions = (
+  url: string,
+  title: string,
+  entered: string[],
+  suggestions?: TagSuggestions,
+  includePrivate = false,
+) =>
+  invoke<RankedTag[]>("rank_tag_suggestions", {
+    url,
+    title,
+    entered,
+    suggestions: suggestions ?? null,
+    includePrivate,
+  });
+export const renameUserTag = (from: string, to: string, dryRun = false) =>
+  invoke<TagMutationResult>("rename_user_tag", { from, to, dryRun });
+export const mergeUserTags = (sources: string[], target: string, dryRun = false) =>
+  invoke<TagMutationResult>("merge_user_tags", { sources, target, dryRun });
+export const deleteUserTag = (tag: string, dryRun = false) =>
+  invoke<TagMutationResult>("delete_user_tag", { tag, dryRun });
+export const getTagBundles = () => invoke<TagBundle[]>("get_tag_bundles");
+export const saveTagBundles = (bundles: TagBundle[]) => invoke<void>("save_tag_bundles", { bundles });
+export const expandTagBundles = (tags: string[]) =>
+  invoke<BundleExpansion>("expand_tag_bundles", { tags });
+export const searchBookmarks = (query: string, limit?: number) =>
+  invoke<ExistingBookmark[]>("search_bookmarks", { query, limit: limit ?? null });
+export const syncBookmarks = () => invoke<number>("sync_bookmarks");
+export const getRecentBookmarks = (count?: number) =>
+  invoke<BookmarkList>("recent_bookmarks", { count: count ?? null });
+export const getReadLaterInbox = () => invoke<ExistingBookmark[]>("read_later_inbox");
+export const markBookmarkRead = (url: string) => invoke<SubmitResult>("mark_bookmark_read", { url });
+export const deleteBookmark = (url: string, confirm = false) =>
+  invoke<DeleteBookmarkResult>("delete_bookmark", { url, confirm });
+export const getUndoStatus = () => invoke<UndoInfo | null>("get_undo_status");
+export const undoLastSubmission = () => invoke<SubmitResult>("undo_last_submission");
+export const getQueueConflicts = () => invoke<QueuedConflict[]>("queue_conflicts");
+export const resolveQueueConflict = (id: number, keepLocal: boolean, remote?: ExistingBookmark | null) =>
+  invoke<void>("resolve_queue_conflict", { id, keepLocal, remote: remote ?? null });
+export const importBookmarks = (path: string, format?: ImportFormat) =>
+  invoke<ImportJob>("import_bookmarks", { path, format: format ?? null });
+export const getImportJobs = () => invoke<ImportJob[]>("import_jobs");
+export const resumeImport = (id: number) => invoke<ImportJob>("resume_import", { id });
+export const exportBookmarks = (path: string, format: ExportFormat, query?: string) =>
+  invoke<number>("export_bookmarks", { path, format, query: query ?? null });
+export const createBackup = (path: string, passphrase?: string) =>
+  invoke<BackupSummary>("create_backup", { path, passphrase: passphrase ?? null });
+export const restoreBackup = (path: string, mode: RestoreMode, passphrase?: string) =>
+  invoke<BackupSummary>("restore_backup", { path, mode, passphrase: passphrase ?? null });
@@ -11,6 +11,7 @@ import {
 interface BookmarkState {
   loading: boolean;
   tokenConfigured: boolean;
+  activeAccount: string;
   queueStats: QueueStats;
   queue: QueueItem[];
   suggestions?: TagSuggestions;
@@ -18,15 +19,23 @@ interface BookmarkState {
   hydrate: () => Promise<void>;
   refreshQueue: () => Promise<void>;
   setTokenConfigured: (configured: boolean) => void;
+  setActiveAccount: (account: string) => void;
   setSuggestions: (suggestions?: TagSuggestions) => void;
   setStatusMessage: (message: string) => void;
 }
 
-const defaultQueueStats: QueueStats = { pending: 0, failed: 0 };
+const defaultQueueStats: QueueStats = {
+  pending: 0,
+  failed: 0,
+  conflicts: 0,
+  paused: false,
+  pausedAccounts: [],
+};
 
 export const useBookmarkStore = create<BookmarkState>((set) => ({
   loading: true,
   tokenConfigured: false,
+  activeAccount: "default",
   queueStats: defaultQueueStats,
   queue: [],
   statusMessage: "",
@@ -38,6 +47,7 @@ export const useBookmarkStore = create<BookmarkState>((set) => ({
       set({
         loading: false,
         tokenConfigured: session.tokenConfigured,
+        activeAccount: session.activeAccount,
         queueStats: session.queueStats,
         queue,
       });
@@ -50,9 +60,17 @@ export const useBookmarkStore = create<BookmarkState>((set) => ({
   },
   refreshQueue: async () => {
     const queue = await getQueue();
-    set({ queue, queueStats: { pending: queue.length, failed: queue.filter((i) => i.attemptCount > 0).length } });
+    set((state) => ({
+      queue,
+      queueStats: {
+        ...state.queueStats,
+        pending: queue.length,
+        failed: queue.filter((i) => i.attemptCount > 0).length,
+      },
+    }));
   },
   setTokenConfigured: (tokenConfigured) => set({ tokenConfigured }),
+  setActiveAccount: (activeAccount) => set({ activeAccount }),
   setSuggestions: (suggestions) => set({ suggestions }),
   setStatusMessage: (statusMessage) => set({ statusMessage }),
 }));